@@ -0,0 +1,35 @@
+//! 模板匹配纯计算部分的基准测试（不含截图和读取模板的 IO 开销）
+//!
+//! 运行：`cargo bench -p image_utils --bench match_template`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use image_utils::image_match::match_only;
+use opencv::core::{Mat, Rect, Scalar, CV_8UC3};
+use opencv::prelude::*;
+
+/// 生成随机噪声源图像，并从中裁剪一块作为模板，保证模板一定能匹配上
+fn fixtures(width: i32, height: i32) -> (Mat, Mat) {
+    let mut src = Mat::new_rows_cols_with_default(height, width, CV_8UC3, Scalar::all(0.0)).unwrap();
+    opencv::core::randu(&mut src, &Scalar::all(0.0), &Scalar::all(255.0)).unwrap();
+
+    let template = Mat::roi(&src, Rect::new(width / 2, height / 2, 48, 48))
+        .unwrap()
+        .clone_pointee();
+
+    (src, template)
+}
+
+fn bench_match_only(c: &mut Criterion) {
+    let (src, template) = fixtures(1280, 720);
+
+    c.bench_function("match_only rgb 1280x720", |b| {
+        b.iter(|| match_only(black_box(&src), black_box(&template), 0.8, true).unwrap())
+    });
+
+    c.bench_function("match_only gray 1280x720", |b| {
+        b.iter(|| match_only(black_box(&src), black_box(&template), 0.8, false).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_match_only);
+criterion_main!(benches);
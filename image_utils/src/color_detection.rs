@@ -1,6 +1,12 @@
+use opencv::core::Scalar;
+use opencv::imgproc;
 use opencv::prelude::{MatTraitConst, MatTraitConstManual};
+use rayon::prelude::*;
+use crate::consts::DEFAULT_ALGORITHM_HINT;
 use crate::image_match_error::ImageMatchError;
+use crate::screenshot_error::ScreenshotError;
 use crate::screenshot::{screenshot_to_mat};
+use crate::types::{ColorMetric, Region, RgbColor};
 
 /// 计算两个颜色之间的差异
 ///
@@ -11,9 +17,7 @@ use crate::screenshot::{screenshot_to_mat};
 /// # 返回
 /// 颜色差异值（曼哈顿距离）
 pub fn calculate_color_difference(color1: (u8, u8, u8), color2: (u8, u8, u8)) -> u32 {
-    (color1.0 as i32 - color2.0 as i32).abs() as u32 +
-        (color1.1 as i32 - color2.1 as i32).abs() as u32 +
-        (color1.2 as i32 - color2.2 as i32).abs() as u32
+    RgbColor::from_tuple(color1).distance(&RgbColor::from_tuple(color2))
 }
 
 /// 屏幕点找色（优化版）
@@ -25,7 +29,7 @@ pub fn calculate_color_difference(color1: (u8, u8, u8), color2: (u8, u8, u8)) ->
 /// - `tolerance`: 容差值
 ///
 /// # 返回
-/// 如果颜色匹配返回 true，否则返回 false
+/// 如果颜色匹配返回 true，否则返回 false；需要知道实际颜色时使用 `get_color_at_point`
 ///
 /// # 示例
 /// ```rust
@@ -33,25 +37,280 @@ pub fn calculate_color_difference(color1: (u8, u8, u8), color2: (u8, u8, u8)) ->
 ///
 /// let found = find_color_at_point(100, 100, (255, 0, 0), 10)?;
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
 pub fn find_color_at_point(
     x: i32,
     y: i32,
     target_rgb: (u8, u8, u8),
     tolerance: u32,
 ) -> Result<bool, ImageMatchError> {
+    get_color_at_point(x, y).map(|rgb| calculate_color_difference(rgb, target_rgb) <= tolerance)
+}
+
+/// 读取屏幕指定点的颜色
+///
+/// 找色失败时可以用它查看该点实际的颜色，据此调整目标颜色或容差
+///
+/// # 参数
+/// - `x`: 屏幕 X 坐标
+/// - `y`: 屏幕 Y 坐标
+///
+/// # 返回
+/// 该点的颜色 (R, G, B)
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::{calculate_color_difference, get_color_at_point};
+///
+/// let rgb = get_color_at_point(100, 100)?;
+/// println!("实际颜色: {:?}，差异: {}", rgb, calculate_color_difference(rgb, (255, 0, 0)));
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn get_color_at_point(x: i32, y: i32) -> Result<(u8, u8, u8), ImageMatchError> {
     // 截取 1x1 像素区域
     let img = screenshot_to_mat(x as u32, y as u32, 1, 1)?;
 
-    // 获取像素颜色（OpenCV 是 BGR 格式）
-    unsafe {
-        let pixel = *img.at_2d_unchecked::<opencv::core::Vec3b>(0, 0)?;
-        let bgr = (pixel[0], pixel[1], pixel[2]);
+    // 获取像素颜色（按通道数读取并转换为 RGB）
+    let channels = pixel_channels(&img)?;
+    let rgb = unsafe { read_rgb_unchecked(&img, channels, 0, 0)? };
+    Ok(rgb)
+}
+
+/// 批量找色时一次截图的最大像素数，点分布过散、包围盒超过该值时改为逐点截图
+const MAX_BATCH_CAPTURE_PIXELS: u64 = 1920 * 1080;
+
+/// 屏幕多点找色 - 一次截图检查多个点
+///
+/// 逐个调用 `find_color_at_point` 需要截图 N 次。这里先计算所有点的包围盒，
+/// 只截图一次，再从截图中读取每个点的颜色。
+/// 点分布过散（包围盒超过 1920x1080 像素）时退化为逐点截图，避免截取过大的区域
+///
+/// # 参数
+/// - `points`: 要检查的屏幕坐标列表
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+///
+/// # 返回
+/// 与 `points` 顺序一致的匹配结果；任一坐标为负数时返回 `NegativeCoordinate`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_colors_at_points;
+///
+/// let results = find_colors_at_points(&[(100, 100), (110, 100), (120, 100)], (255, 0, 0), 10)?;
+/// let all_red = results.iter().all(|&found| found);
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_colors_at_points(
+    points: &[(i32, i32)],
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+) -> Result<Vec<bool>, ImageMatchError> {
+    let Some(&(first_x, first_y)) = points.first() else {
+        return Ok(Vec::new());
+    };
+
+    // 截图坐标是无符号的，负数坐标无法截取；排除负数后包围盒的宽高也不会溢出
+    if let Some(&(x, y)) = points.iter().find(|&&(x, y)| x < 0 || y < 0) {
+        return Err(ScreenshotError::NegativeCoordinate { x, y }.into());
+    }
+
+    // 计算包围盒（用 i64 计算宽高，避免溢出）
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (first_x, first_y, first_x, first_y);
+    for &(x, y) in points {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+
+    // 坐标均非负，宽高最大为 i32::MAX + 1，可以放入 u32
+    let (min_x_u32, min_y_u32) = (min_x as u32, min_y as u32);
+    let width = (max_x as i64 - min_x as i64 + 1) as u32;
+    let height = (max_y as i64 - min_y as i64 + 1) as u32;
+
+    if width as u64 * height as u64 > MAX_BATCH_CAPTURE_PIXELS {
+        return points
+            .iter()
+            .map(|&(x, y)| find_color_at_point(x, y, target_rgb, tolerance))
+            .collect();
+    }
+
+    let img = screenshot_to_mat(min_x_u32, min_y_u32, width, height)?;
+    let channels = pixel_channels(&img)?;
+    if img.cols() < width as i32 || img.rows() < height as i32 {
+        return Err(ImageMatchError::OpenCV(opencv::Error::new(
+            opencv::core::StsOutOfRange,
+            format!("截图尺寸 {}x{} 小于请求的 {}x{}", img.cols(), img.rows(), width, height),
+        )));
+    }
+
+    points
+        .iter()
+        .map(|&(x, y)| {
+            // 所有点都在包围盒内，上面已确认截图覆盖整个包围盒
+            let rgb = unsafe { read_rgb_unchecked(&img, channels, y - min_y, x - min_x)? };
+            Ok(calculate_color_difference(rgb, target_rgb) <= tolerance)
+        })
+        .collect()
+}
+
+/// 读取小数坐标处的颜色（双线性插值）
+///
+/// 截取 (x, y) 所在的 2x2 邻域，按小数部分对四个像素做双线性插值，
+/// 适用于亚像素精度的匹配结果做颜色校验
+///
+/// # 参数
+/// - `x`: 屏幕 X 坐标（可以是小数）
+/// - `y`: 屏幕 Y 坐标（可以是小数）
+///
+/// # 返回
+/// 插值后的颜色，各通道四舍五入到 u8
+///
+/// # 注意
+/// 坐标必须非负，且 (floor(x) + 1, floor(y) + 1) 仍需在屏幕范围内
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::read_color_bilinear;
+///
+/// let color = read_color_bilinear(100.25, 200.5)?;
+/// println!("颜色: {:?}", color.to_tuple());
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn read_color_bilinear(x: f64, y: f64) -> anyhow::Result<RgbColor> {
+    if x < 0.0 || y < 0.0 {
+        anyhow::bail!("坐标不能为负数: ({}, {})", x, y);
+    }
+
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+
+    // 截取 2x2 邻域
+    let img = screenshot_to_mat(x0 as u32, y0 as u32, 2, 2)?;
+
+    let mut channels = [0.0f64; 3];
+    for (dy, wy) in [(0, 1.0 - fy), (1, fy)] {
+        for (dx, wx) in [(0, 1.0 - fx), (1, fx)] {
+            let color = read_mat_color(&img, dy, dx)?;
+            let weight = wx * wy;
+            channels[0] += color.r as f64 * weight;
+            channels[1] += color.g as f64 * weight;
+            channels[2] += color.b as f64 * weight;
+        }
+    }
+
+    Ok(RgbColor::new(
+        channels[0].round().clamp(0.0, 255.0) as u8,
+        channels[1].round().clamp(0.0, 255.0) as u8,
+        channels[2].round().clamp(0.0, 255.0) as u8,
+    ))
+}
+
+/// 读取一行像素的颜色（水平剖面）
+///
+/// 截取从 (x, y) 开始、宽 `length`、高 1 的区域，按从左到右的顺序返回颜色。
+/// 适用于分析渐变条、进度条的填充程度或查找边缘
+///
+/// # 参数
+/// - `x`: 起点 X 坐标
+/// - `y`: 起点 Y 坐标
+/// - `length`: 采样长度（像素）
+///
+/// # 返回
+/// 长度为 `length` 的颜色列表
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::sample_row;
+///
+/// let profile = sample_row(100, 50, 200)?;
+/// let filled = profile.iter().take_while(|c| c.g > 150).count();
+/// println!("血条剩余 {}%", filled * 100 / profile.len());
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
+pub fn sample_row(x: u32, y: u32, length: u32) -> anyhow::Result<Vec<RgbColor>> {
+    let img = screenshot_to_mat(x, y, length, 1)?;
+
+    (0..img.cols())
+        .map(|col| read_mat_color(&img, 0, col))
+        .collect()
+}
+
+/// 读取一列像素的颜色（垂直剖面）
+///
+/// 截取从 (x, y) 开始、宽 1、高 `length` 的区域，按从上到下的顺序返回颜色
+///
+/// # 参数
+/// - `x`: 起点 X 坐标
+/// - `y`: 起点 Y 坐标
+/// - `length`: 采样长度（像素）
+///
+/// # 返回
+/// 长度为 `length` 的颜色列表
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
+pub fn sample_column(x: u32, y: u32, length: u32) -> anyhow::Result<Vec<RgbColor>> {
+    let img = screenshot_to_mat(x, y, 1, length)?;
+
+    (0..img.rows())
+        .map(|row| read_mat_color(&img, row, 0))
+        .collect()
+}
+
+/// 读取图像中指定像素的颜色（支持灰度、BGR、BGRA）
+fn read_mat_color(mat: &opencv::core::Mat, row: i32, col: i32) -> anyhow::Result<RgbColor> {
+    let channels = pixel_channels(mat)?;
+    if row < 0 || row >= mat.rows() || col < 0 || col >= mat.cols() {
+        anyhow::bail!("像素坐标超出图像范围: ({}, {})", col, row);
+    }
 
-        // 转换为 RGB
-        let rgb = (bgr.2, bgr.1, bgr.0);
+    let rgb = unsafe { read_rgb_unchecked(mat, channels, row, col)? };
+    Ok(RgbColor::from_tuple(rgb))
+}
+
+/// 检查图像是否为颜色检测支持的格式，返回通道数
+///
+/// 支持 8 位的 1 通道（灰度）、3 通道（BGR）、4 通道（BGRA）图像
+fn pixel_channels(mat: &opencv::core::Mat) -> opencv::Result<i32> {
+    let channels = mat.channels();
+    if mat.depth() != opencv::core::CV_8U || !matches!(channels, 1 | 3 | 4) {
+        return Err(opencv::Error::new(
+            opencv::core::StsUnsupportedFormat,
+            format!("不支持的图像格式: 深度 {}，通道数 {}", mat.depth(), channels),
+        ));
+    }
+    Ok(channels)
+}
 
-        let diff = calculate_color_difference(rgb, target_rgb);
-        Ok(diff <= tolerance)
+/// 按通道数读取像素并转换为 RGB
+///
+/// 灰度像素三个通道取相同的值，BGRA 像素忽略 Alpha 通道
+///
+/// # Safety
+/// 调用方需保证 (row, col) 在图像范围内，且 `channels` 来自 `pixel_channels(mat)`
+unsafe fn read_rgb_unchecked(
+    mat: &opencv::core::Mat,
+    channels: i32,
+    row: i32,
+    col: i32,
+) -> opencv::Result<(u8, u8, u8)> {
+    unsafe {
+        match channels {
+            1 => {
+                let gray = *mat.at_2d_unchecked::<u8>(row, col)?;
+                Ok((gray, gray, gray))
+            }
+            4 => {
+                let pixel = *mat.at_2d_unchecked::<opencv::core::Vec4b>(row, col)?;
+                Ok((pixel[2], pixel[1], pixel[0]))
+            }
+            _ => {
+                let pixel = *mat.at_2d_unchecked::<opencv::core::Vec3b>(row, col)?;
+                Ok((pixel[2], pixel[1], pixel[0]))
+            }
+        }
     }
 }
 
@@ -81,6 +340,7 @@ pub fn find_color_at_point(
 ///
 /// let found = find_color_in_region(100, 100, 200, 150, (255, 0, 0), 10)?;
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
 pub fn find_color_in_region(
     x1: u32,
     y1: u32,
@@ -92,31 +352,24 @@ pub fn find_color_in_region(
     // 截图
     let img = screenshot_to_mat(x1, y1, width, height)?;
 
-    // 转换为 RGB（OpenCV 默认是 BGR）
-    let rows = img.rows();
-    let cols = img.cols();
-
-    // 遍历所有像素
-    for y in 0..rows {
-        for x in 0..cols {
-            unsafe {
-                let pixel = *img.at_2d_unchecked::<opencv::core::Vec3b>(y, x)?;
-                let bgr = (pixel[0], pixel[1], pixel[2]);
-
-                // 转换为 RGB
-                let rgb = (bgr.2, bgr.1, bgr.0);
-
-                let diff = calculate_color_difference(rgb, target_rgb);
-                if diff <= tolerance {
-                    return Ok(true);
-                }
-            }
-        }
-    }
-
-    Ok(false)
+    Ok(find_color_in_mat_coord(&img, target_rgb, tolerance, (x1, y1))?.is_some())
 }
 
+/// 屏幕区域找色 - 区域由 `Region` 指定，返回布尔值
+///
+/// 与 `find_color_in_region` 相同，用 `Region` 代替四个整数参数，避免宽高与右下角坐标混用
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_in;
+/// use image_utils::types::Region;
+///
+/// let found = find_color_in(Region::from_corners(100, 100, 300, 250)?, (255, 0, 0), 10)?;
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_in(region: Region, target_rgb: (u8, u8, u8), tolerance: u32) -> anyhow::Result<bool> {
+    find_color_in_region(region.x, region.y, region.width, region.height, target_rgb, tolerance)
+}
 
 /// 屏幕区域找色（坐标版优化版）- 返回坐标
 ///
@@ -140,6 +393,7 @@ pub fn find_color_in_region(
 ///     println!("找到颜色，坐标: ({}, {})", x, y);
 /// }
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
 pub fn find_color_in_region_coord(
     x1: u32,
     y1: u32,
@@ -151,27 +405,804 @@ pub fn find_color_in_region_coord(
     // 截图
     let img = screenshot_to_mat(x1, y1, width, height)?;
 
+    // 未找到时返回 (0, 0)
+    Ok(find_color_in_mat_coord(&img, target_rgb, tolerance, (x1, y1))?.unwrap_or((0, 0)))
+}
+
+/// 屏幕区域找色（指定颜色差异的计算方式）- 返回坐标
+///
+/// 与 `find_color_in_region_coord` 相同，但可以选择颜色差异的计算方式，
+/// `ColorMetric::Manhattan` 时两者完全一致
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+/// - `metric`: 颜色差异的计算方式
+///
+/// # 返回
+/// 如果找到，返回绝对坐标 (x, y)，否则返回 (0, 0)
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_in_region_coord_with_metric(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    metric: ColorMetric,
+) -> anyhow::Result<(u32, u32)> {
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    // 未找到时返回 (0, 0)
+    let found = find_color_in_mat_coord_with_metric(&img, target_rgb, tolerance, (x1, y1), metric)?;
+    Ok(found.unwrap_or((0, 0)))
+}
+
+/// 屏幕区域找色（多线程版）- 返回布尔值
+///
+/// 与 `find_color_in_region` 相同，但使用 `find_color_in_mat_threaded` 并行扫描，
+/// 任一线程找到匹配像素后立即停止，适合全屏等大区域
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+///
+/// # 返回
+/// 如果找到匹配颜色返回 true，否则返回 false
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_in_region_threaded(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+) -> anyhow::Result<bool> {
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    find_color_in_mat_threaded(&img, target_rgb, tolerance)
+}
+
+/// 屏幕区域找色（多线程坐标版）- 返回坐标
+///
+/// 与 `find_color_in_region_coord` 相同，但按行并行扫描。
+/// 返回的坐标与单线程版本一致（最靠上、其次最靠左的匹配像素）
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+///
+/// # 返回
+/// 如果找到，返回绝对坐标 (x, y)，否则返回 (0, 0)
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_in_region_coord_threaded;
+///
+/// let (x, y) = find_color_in_region_coord_threaded(0, 0, 2560, 1440, (255, 0, 0), 10)?;
+/// if x != 0 || y != 0 {
+///     println!("找到颜色，坐标: ({}, {})", x, y);
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_in_region_coord_threaded(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+) -> anyhow::Result<(u32, u32)> {
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    // 未找到时返回 (0, 0)
+    Ok(find_color_in_mat_coord_threaded(&img, target_rgb, tolerance, (x1, y1))?.unwrap_or((0, 0)))
+}
+
+/// 屏幕区域找最接近的颜色 - 返回坐标和差异值
+///
+/// 不使用容差，总是返回区域内与目标颜色差异最小的像素，
+/// 用于在找色失败时排查"最接近的颜色差了多少"，据此调整容差
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+///
+/// # 返回
+/// ((x, y), 差异值)，坐标为绝对坐标，差异值与 `calculate_color_difference` 一致；
+/// 有多个像素差异相同时返回扫描顺序中的第一个
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::closest_color_in_region;
+///
+/// let ((x, y), diff) = closest_color_in_region(100, 100, 200, 150, (255, 0, 0))?;
+/// println!("最接近的颜色在 ({}, {})，差异 {}", x, y, diff);
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn closest_color_in_region(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+) -> anyhow::Result<((u32, u32), u32)> {
+    // 截图
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
     let rows = img.rows();
     let cols = img.cols();
 
+    let channels = pixel_channels(&img)?;
+    let mut closest: Option<((u32, u32), u32)> = None;
+
     // 遍历所有像素
-    for y in 0..rows {
+    'scan: for y in 0..rows {
         for x in 0..cols {
             unsafe {
-                let pixel = *img.at_2d_unchecked::<opencv::core::Vec3b>(y, x)?;
-                let bgr = (pixel[0], pixel[1], pixel[2]);
-
-                // 转换为 RGB
-                let rgb = (bgr.2, bgr.1, bgr.0);
+                let rgb = read_rgb_unchecked(&img, channels, y, x)?;
 
                 let diff = calculate_color_difference(rgb, target_rgb);
-                if diff <= tolerance {
+                if closest.is_none_or(|(_, best)| diff < best) {
+                    closest = Some(((x1 + x as u32, y1 + y as u32), diff));
+                    if diff == 0 {
+                        // 完全匹配，不可能更接近
+                        break 'scan;
+                    }
+                }
+            }
+        }
+    }
+
+    closest.ok_or_else(|| anyhow::anyhow!("截图区域为空: {}x{}", width, height))
+}
+
+/// 在给定的图像中找色 - 返回坐标
+///
+/// 不截图，直接在传入的 Mat 中查找，可以复用同一张截图进行多次查找，也便于用固定图片测试
+///
+/// # 参数
+/// - `mat`: 源图像（OpenCV Mat，8 位灰度、BGR 或 BGRA 格式）
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+/// - `offset`: 图像左上角对应的坐标偏移 (x, y)，会加到返回的坐标上
+///
+/// # 返回
+/// 如果找到，返回第一个匹配像素的坐标 (offset.x + x, offset.y + y)，否则返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_in_mat_coord;
+/// use image_utils::screenshot::screenshot_to_mat;
+///
+/// let img = screenshot_to_mat(100, 100, 200, 150)?;
+/// let found = find_color_in_mat_coord(&img, (255, 0, 0), 10, (100, 100))?;
+/// ```
+pub fn find_color_in_mat_coord(
+    mat: &opencv::core::Mat,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    offset: (u32, u32),
+) -> anyhow::Result<Option<(u32, u32)>> {
+    find_color_in_mat_coord_with_metric(mat, target_rgb, tolerance, offset, ColorMetric::Manhattan)
+}
+
+/// 在给定的图像中找色（指定颜色差异的计算方式）- 返回坐标
+///
+/// 与 `find_color_in_mat_coord` 相同，但可以选择用欧几里得距离判断颜色是否在容差内
+///
+/// # 参数
+/// - `mat`: 源图像（OpenCV Mat，8 位灰度、BGR 或 BGRA 格式）
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值，按 `metric` 计算的差异小于等于该值即为匹配
+/// - `offset`: 图像左上角对应的坐标偏移 (x, y)，会加到返回的坐标上
+/// - `metric`: 颜色差异的计算方式
+///
+/// # 返回
+/// 如果找到，返回第一个匹配像素的坐标 (offset.x + x, offset.y + y)，否则返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_in_mat_coord_with_metric;
+/// use image_utils::screenshot::screenshot_to_mat;
+/// use image_utils::types::ColorMetric;
+///
+/// let img = screenshot_to_mat(100, 100, 200, 150)?;
+/// let found = find_color_in_mat_coord_with_metric(&img, (255, 0, 0), 20, (100, 100), ColorMetric::Euclidean)?;
+/// ```
+pub fn find_color_in_mat_coord_with_metric(
+    mat: &opencv::core::Mat,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    offset: (u32, u32),
+    metric: ColorMetric,
+) -> anyhow::Result<Option<(u32, u32)>> {
+    let channels = pixel_channels(mat)?;
+
+    // 逐行遍历所有像素
+    for y in 0..mat.rows() {
+        if let Some(x) = first_match_in_row(mat, channels, y, target_rgb, tolerance, metric)? {
+            return Ok(Some((offset.0 + x as u32, offset.1 + y as u32)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// 在给定的图像中找色（多线程版）- 返回坐标
+///
+/// 与 `find_color_in_mat_coord` 相同，但按行分配给 rayon 线程池并行扫描，适合全屏等大区域。
+/// 各行独立查找本行的第一个匹配像素，最后取 y 最小的行，
+/// 因此结果与单线程版本完全一致（y 最小，其次 x 最小），不受线程调度影响
+///
+/// # 参数
+/// - `mat`: 源图像（OpenCV Mat，8 位灰度、BGR 或 BGRA 格式）
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+/// - `offset`: 图像左上角对应的坐标偏移 (x, y)，会加到返回的坐标上
+///
+/// # 返回
+/// 如果找到，返回最靠上、最靠左的匹配像素坐标，否则返回 None
+pub fn find_color_in_mat_coord_threaded(
+    mat: &opencv::core::Mat,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    offset: (u32, u32),
+) -> anyhow::Result<Option<(u32, u32)>> {
+    let channels = pixel_channels(mat)?;
+
+    let found = (0..mat.rows())
+        .into_par_iter()
+        .map(|y| {
+            first_match_in_row(mat, channels, y, target_rgb, tolerance, ColorMetric::Manhattan)
+                .map(|found| found.map(|x| (x, y)))
+        })
+        // find_map_first 按行号顺序取第一个结果，保证坐标确定；读取出错时同样提前结束
+        .find_map_first(|row| row.transpose())
+        .transpose()?;
+
+    Ok(found.map(|(x, y)| (offset.0 + x as u32, offset.1 + y as u32)))
+}
+
+/// 在给定的图像中找色（多线程版）- 返回布尔值
+///
+/// 只关心是否存在匹配像素，任一线程找到后其余线程立即停止扫描
+///
+/// # 参数
+/// - `mat`: 源图像（OpenCV Mat，8 位灰度、BGR 或 BGRA 格式）
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+pub fn find_color_in_mat_threaded(
+    mat: &opencv::core::Mat,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+) -> anyhow::Result<bool> {
+    let channels = pixel_channels(mat)?;
+
+    let found = (0..mat.rows())
+        .into_par_iter()
+        .map(|y| first_match_in_row(mat, channels, y, target_rgb, tolerance, ColorMetric::Manhattan))
+        .find_map_any(|row| row.transpose())
+        .transpose()?;
+
+    Ok(found.is_some())
+}
+
+/// 查找图像第 `y` 行中第一个与目标颜色匹配的像素，返回其 X 坐标
+///
+/// `channels` 需来自 `pixel_channels(mat)`，`y` 需在图像范围内
+fn first_match_in_row(
+    mat: &opencv::core::Mat,
+    channels: i32,
+    y: i32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    metric: ColorMetric,
+) -> opencv::Result<Option<i32>> {
+    let target = RgbColor::from_tuple(target_rgb);
+    for x in 0..mat.cols() {
+        let rgb = unsafe { read_rgb_unchecked(mat, channels, y, x)? };
+        if metric.within(&RgbColor::from_tuple(rgb), &target, tolerance) {
+            return Ok(Some(x));
+        }
+    }
+
+    Ok(None)
+}
+
+/// 屏幕区域找色（质心版）- 返回所有匹配像素的中心
+///
+/// `find_color_in_region_coord` 返回的是第一个匹配像素（色块的左上角），
+/// 这里对所有匹配像素的坐标取平均，得到色块的中心，更适合用作点击位置
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+///
+/// # 返回
+/// 所有匹配像素坐标平均值（四舍五入）的绝对坐标，没有匹配像素时返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_centroid_in_region;
+///
+/// if let Some((x, y)) = find_color_centroid_in_region(100, 100, 200, 150, (0, 200, 0), 20)? {
+///     println!("绿色按钮中心: ({}, {})", x, y);
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_centroid_in_region(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+) -> anyhow::Result<Option<(u32, u32)>> {
+    // 截图
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    let channels = pixel_channels(&img)?;
+    let (mut sum_x, mut sum_y, mut count) = (0u64, 0u64, 0u64);
+
+    // 遍历所有像素，累加匹配像素的坐标
+    for y in 0..img.rows() {
+        for x in 0..img.cols() {
+            unsafe {
+                let rgb = read_rgb_unchecked(&img, channels, y, x)?;
+                if calculate_color_difference(rgb, target_rgb) <= tolerance {
+                    sum_x += x as u64;
+                    sum_y += y as u64;
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    if count == 0 {
+        return Ok(None);
+    }
+
+    let center_x = (sum_x as f64 / count as f64).round() as u32;
+    let center_y = (sum_y as f64 / count as f64).round() as u32;
+    Ok(Some((x1 + center_x, y1 + center_y)))
+}
+
+/// 屏幕区域找色 - 返回所有匹配颜色的坐标（逐像素）
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+///
+/// # 返回
+/// 所有匹配像素的绝对坐标 (x1 + x, y1 + y)，按逐行扫描顺序排列
+///
+/// # 注意
+/// 会检查区域内的每个像素，大区域中匹配的像素可能非常多（全屏可达数百万个），
+/// 只需要大致分布时使用 `find_all_colors_in_region_step` 抽样，
+/// 需要限制数量或按色块去重时使用 `find_all_color_coords`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_all_colors_in_region;
+///
+/// let coords = find_all_colors_in_region(100, 100, 200, 150, (255, 0, 0), 10)?;
+/// println!("共有 {} 个红色像素", coords.len());
+/// ```
+pub fn find_all_colors_in_region(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+) -> anyhow::Result<Vec<(u32, u32)>> {
+    find_all_colors_in_region_step(x1, y1, width, height, target_rgb, tolerance, 1)
+}
+
+/// 屏幕区域找色 - 每隔 `step` 个像素抽样，返回所有匹配颜色的坐标
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+/// - `step`: 抽样间隔，行和列都每隔 `step` 个像素检查一次，0 按 1 处理
+///
+/// # 返回
+/// 抽样到的匹配像素的绝对坐标 (x1 + x, y1 + y)，按逐行扫描顺序排列
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
+pub fn find_all_colors_in_region_step(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    step: usize,
+) -> anyhow::Result<Vec<(u32, u32)>> {
+    // 截图
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    let channels = pixel_channels(&img)?;
+    let step = step.max(1);
+    let mut coords = Vec::new();
+
+    // 按抽样间隔遍历像素
+    for y in (0..img.rows()).step_by(step) {
+        for x in (0..img.cols()).step_by(step) {
+            unsafe {
+                let rgb = read_rgb_unchecked(&img, channels, y, x)?;
+                if calculate_color_difference(rgb, target_rgb) <= tolerance {
+                    coords.push((x1 + x as u32, y1 + y as u32));
+                }
+            }
+        }
+    }
+
+    Ok(coords)
+}
+
+/// 屏幕区域找色 - 返回所有匹配颜色的坐标
+///
+/// 整个区域内匹配的像素可能多达数十万个，可以用 `max_results` 限制数量，
+/// 用 `dedup_radius` 把同一个色块内的像素合并为一个坐标（每个标记只返回一个点）
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_rgb`: 目标颜色 (R, G, B)
+/// - `tolerance`: 容差值
+/// - `max_results`: 最多返回的坐标数量，None 表示不限制
+/// - `dedup_radius`: 去重半径（像素），与已接受坐标的距离不超过该值的匹配会被忽略，None 表示不去重
+///
+/// # 返回
+/// 按逐行扫描顺序排列的绝对坐标列表
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_all_color_coords;
+///
+/// // 小地图上的红点，每个红点只取一个坐标，最多 20 个
+/// let markers = find_all_color_coords(1700, 50, 200, 200, (255, 0, 0), 20, Some(20), Some(5))?;
+/// ```
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
+pub fn find_all_color_coords(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_rgb: (u8, u8, u8),
+    tolerance: u32,
+    max_results: Option<usize>,
+    dedup_radius: Option<u32>,
+) -> anyhow::Result<Vec<(u32, u32)>> {
+    let mut coords = Vec::new();
+    if max_results == Some(0) {
+        return Ok(coords);
+    }
+
+    // 截图
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    let rows = img.rows();
+    let cols = img.cols();
+    let channels = pixel_channels(&img)?;
+    let radius_sq = dedup_radius.map(|radius| radius as u64 * radius as u64);
+
+    // 遍历所有像素
+    'scan: for y in 0..rows {
+        for x in 0..cols {
+            let rgb = unsafe { read_rgb_unchecked(&img, channels, y, x)? };
+            if calculate_color_difference(rgb, target_rgb) > tolerance {
+                continue;
+            }
+
+            let coord = (x1 + x as u32, y1 + y as u32);
+            if let Some(radius_sq) = radius_sq {
+                let is_duplicate = coords.iter().any(|&(cx, cy): &(u32, u32)| {
+                    let dx = cx.abs_diff(coord.0) as u64;
+                    let dy = cy.abs_diff(coord.1) as u64;
+                    dx * dx + dy * dy <= radius_sq
+                });
+                if is_duplicate {
+                    continue;
+                }
+            }
+
+            coords.push(coord);
+            if max_results.is_some_and(|max| coords.len() >= max) {
+                break 'scan;
+            }
+        }
+    }
+
+    Ok(coords)
+}
+
+/// 统计两张图像中颜色差异超过容差的像素数量
+///
+/// 逐像素比较，差异按 `calculate_color_difference` 计算。
+/// 与整体平均差异相比，更适合在静态背景上检测小范围的局部变化
+///
+/// # 参数
+/// - `a`: 图像 1（8 位灰度、BGR 或 BGRA 格式）
+/// - `b`: 图像 2，尺寸必须与图像 1 相同
+/// - `tolerance`: 单个像素的颜色容差
+///
+/// # 返回
+/// 差异超过容差的像素数量；两张图像尺寸不同时返回 OpenCV 错误
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::count_differing_pixels;
+/// use image_utils::screenshot::screenshot_to_mat;
+///
+/// let before = screenshot_to_mat(100, 100, 200, 150)?;
+/// let after = screenshot_to_mat(100, 100, 200, 150)?;
+/// if count_differing_pixels(&before, &after, 30)? > 50 {
+///     println!("区域发生了变化");
+/// }
+/// ```
+pub fn count_differing_pixels(
+    a: &opencv::core::Mat,
+    b: &opencv::core::Mat,
+    tolerance: u32,
+) -> Result<u32, ImageMatchError> {
+    if a.size()? != b.size()? {
+        return Err(opencv::Error::new(
+            opencv::core::StsUnmatchedSizes,
+            format!("图像尺寸不一致: {:?} 与 {:?}", a.size()?, b.size()?),
+        )
+        .into());
+    }
+
+    let channels_a = pixel_channels(a)?;
+    let channels_b = pixel_channels(b)?;
+
+    let mut count = 0;
+    for y in 0..a.rows() {
+        for x in 0..a.cols() {
+            unsafe {
+                let rgb_a = read_rgb_unchecked(a, channels_a, y, x)?;
+                let rgb_b = read_rgb_unchecked(b, channels_b, y, x)?;
+                if calculate_color_difference(rgb_a, rgb_b) > tolerance {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// 判断 HSV 颜色是否在目标颜色的容差范围内（各通道独立容差）
+///
+/// 色相按 OpenCV 的 0-180 环形处理，例如 179 与 1 的色相差为 2
+///
+/// # 参数
+/// - `hsv`: 待比较的颜色 (H, S, V)
+/// - `target_hsv`: 目标颜色 (H, S, V)，H 范围 0-180，S、V 范围 0-255
+/// - `tolerance`: 各通道容差 (H, S, V)
+pub fn hsv_within_tolerance(hsv: (u8, u8, u8), target_hsv: (u8, u8, u8), tolerance: (u8, u8, u8)) -> bool {
+    let hue_diff = hsv.0.abs_diff(target_hsv.0);
+    let hue_diff = hue_diff.min(180u8.saturating_sub(hue_diff));
+
+    hue_diff <= tolerance.0
+        && hsv.1.abs_diff(target_hsv.1) <= tolerance.1
+        && hsv.2.abs_diff(target_hsv.2) <= tolerance.2
+}
+
+/// 屏幕点找色（HSV 版）
+///
+/// 与 RGB 曼哈顿距离相比，HSV 可以单独放宽明度（V）的容差，在亮度变化时更稳定
+///
+/// # 参数
+/// - `x`: 屏幕 X 坐标
+/// - `y`: 屏幕 Y 坐标
+/// - `target_hsv`: 目标颜色 (H, S, V)，H 范围 0-180，S、V 范围 0-255
+/// - `tolerance`: 各通道容差 (H, S, V)
+///
+/// # 返回
+/// 如果颜色匹配返回 true，否则返回 false
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_at_point_hsv;
+///
+/// // 红色，允许明度有较大变化
+/// let found = find_color_at_point_hsv(100, 100, (0, 200, 200), (5, 40, 80))?;
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_at_point_hsv(
+    x: i32,
+    y: i32,
+    target_hsv: (u8, u8, u8),
+    tolerance: (u8, u8, u8),
+) -> Result<bool, ImageMatchError> {
+    // 截取 1x1 像素区域
+    let img = screenshot_to_mat(x as u32, y as u32, 1, 1)?;
+    let hsv = bgr_to_hsv(&img)?;
+
+    let pixel = *hsv.at_2d::<opencv::core::Vec3b>(0, 0)?;
+    Ok(hsv_within_tolerance((pixel[0], pixel[1], pixel[2]), target_hsv, tolerance))
+}
+
+/// 屏幕区域找色（HSV 版）- 返回布尔值
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_hsv`: 目标颜色 (H, S, V)，H 范围 0-180，S、V 范围 0-255
+/// - `tolerance`: 各通道容差 (H, S, V)
+///
+/// # 返回
+/// 如果找到匹配颜色返回 true，否则返回 false
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_in_region_hsv(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_hsv: (u8, u8, u8),
+    tolerance: (u8, u8, u8),
+) -> anyhow::Result<bool> {
+    Ok(find_color_in_region_hsv_coord(x1, y1, width, height, target_hsv, tolerance)?.is_some())
+}
+
+/// 屏幕区域找色（HSV 版）- 返回坐标
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `target_hsv`: 目标颜色 (H, S, V)，H 范围 0-180，S、V 范围 0-255
+/// - `tolerance`: 各通道容差 (H, S, V)
+///
+/// # 返回
+/// 如果找到，返回第一个匹配像素的绝对坐标 (x, y)，否则返回 None
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_in_region_hsv_coord(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    target_hsv: (u8, u8, u8),
+    tolerance: (u8, u8, u8),
+) -> anyhow::Result<Option<(u32, u32)>> {
+    // 截图
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+    let hsv = bgr_to_hsv(&img)?;
+
+    // 遍历所有像素
+    for y in 0..hsv.rows() {
+        for x in 0..hsv.cols() {
+            unsafe {
+                let pixel = *hsv.at_2d_unchecked::<opencv::core::Vec3b>(y, x)?;
+                if hsv_within_tolerance((pixel[0], pixel[1], pixel[2]), target_hsv, tolerance) {
+                    // 返回绝对坐标
+                    return Ok(Some((x1 + x as u32, y1 + y as u32)));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// 将 BGR 图像转换为 HSV
+fn bgr_to_hsv(img: &opencv::core::Mat) -> opencv::Result<opencv::core::Mat> {
+    let mut hsv = opencv::core::Mat::default();
+    imgproc::cvt_color(img, &mut hsv, imgproc::COLOR_BGR2HSV, 0, DEFAULT_ALGORITHM_HINT)?;
+    Ok(hsv)
+}
+
+/// 屏幕区域找色（范围版）- 返回第一个落在颜色范围内的坐标
+///
+/// 与单色 + 容差的方式不同，这里使用上下界描述一个颜色范围（例如"任意深浅的绿色"），
+/// 由 OpenCV 的 `in_range` 生成掩码
+///
+/// # 参数
+/// - `x1`: 区域左上角 X 坐标
+/// - `y1`: 区域左上角 Y 坐标
+/// - `width`: 区域宽度
+/// - `height`: 区域高度
+/// - `lower`: 颜色下界（闭区间）
+/// - `upper`: 颜色上界（闭区间）
+/// - `hsv`: 为 false 时上下界按 (R, G, B) 解释；为 true 时按 OpenCV 的 (H, S, V) 解释，
+///   其中 H 范围为 0-180，S、V 范围为 0-255
+///
+/// # 返回
+/// 如果找到，返回第一个（逐行扫描）在范围内的像素的绝对坐标 (x, y)，否则返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::color_detection::find_color_range_coord;
+///
+/// // 查找任意深浅的绿色
+/// let found = find_color_range_coord(100, 100, 200, 150, (35, 80, 80), (85, 255, 255), true)?;
+/// if let Some((x, y)) = found {
+///     println!("找到颜色，坐标: ({}, {})", x, y);
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_color_range_coord(
+    x1: u32,
+    y1: u32,
+    width: u32,
+    height: u32,
+    lower: (u8, u8, u8),
+    upper: (u8, u8, u8),
+    hsv: bool,
+) -> anyhow::Result<Option<(u32, u32)>> {
+    // 截图
+    let img = screenshot_to_mat(x1, y1, width, height)?;
+
+    let (src, lower_bound, upper_bound) = if hsv {
+        let mut hsv_img = opencv::core::Mat::default();
+        imgproc::cvt_color(&img, &mut hsv_img, imgproc::COLOR_BGR2HSV, 0, DEFAULT_ALGORITHM_HINT)?;
+        (
+            hsv_img,
+            Scalar::new(lower.0 as f64, lower.1 as f64, lower.2 as f64, 0.0),
+            Scalar::new(upper.0 as f64, upper.1 as f64, upper.2 as f64, 0.0),
+        )
+    } else {
+        // 截图是 BGR 格式，上下界需要按 BGR 顺序排列
+        (
+            img,
+            Scalar::new(lower.2 as f64, lower.1 as f64, lower.0 as f64, 0.0),
+            Scalar::new(upper.2 as f64, upper.1 as f64, upper.0 as f64, 0.0),
+        )
+    };
+
+    // 生成单通道掩码，范围内的像素为 255
+    let mut mask = opencv::core::Mat::default();
+    opencv::core::in_range(&src, &lower_bound, &upper_bound, &mut mask)?;
+
+    let rows = mask.rows();
+    let cols = mask.cols();
+
+    for y in 0..rows {
+        for x in 0..cols {
+            unsafe {
+                if *mask.at_2d_unchecked::<u8>(y, x)? != 0 {
                     // 返回绝对坐标
-                    return Ok((x1 + x as u32, y1 + y as u32));
+                    return Ok(Some((x1 + x as u32, y1 + y as u32)));
                 }
             }
         }
     }
 
-    Ok((0, 0))
+    Ok(None)
 }
\ No newline at end of file
@@ -1,14 +1,18 @@
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 use std::path::Path;
-use std::sync::Arc;
-use opencv::core::{MatTraitConst};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use opencv::core::{MatTrait, MatTraitConst};
 use opencv::{imgcodecs, imgproc};
 use opencv::prelude::MatTraitConstManual;
 use rayon::prelude::*;
 use crate::consts::DEFAULT_ALGORITHM_HINT;
 use crate::image_match_error::ImageMatchError;
 use crate::screenshot::{screenshot_to_mat, screenshot_to_mat_gray};
-use crate::types::{MatchResult, Point};
+use crate::types::{MatchMethod, MatchResult, Point, RgbColor};
 
 /// 读取图像（兼容 aircv.imread）
 ///
@@ -25,6 +29,67 @@ pub fn read_image(path: &str) -> Result<opencv::core::Mat, ImageMatchError> {
     Ok(img)
 }
 
+/// 从内存中的图像文件数据读取图像（PNG、BMP、JPEG 等编码后的字节）
+///
+/// 与 `read_image` 相同，但数据来自内存，适合用 `include_bytes!` 嵌入到可执行文件中的模板
+///
+/// # 参数
+/// - `data`: 图像文件的完整字节内容
+///
+/// # 返回
+/// OpenCV Mat 格式的图像（BGR）；数据为空或无法解码时返回 `CanNotDecodeImage`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::read_image_from_bytes;
+///
+/// static OK_BUTTON: &[u8] = include_bytes!("../assets/ok.png");
+/// let template = read_image_from_bytes(OK_BUTTON)?;
+/// ```
+pub fn read_image_from_bytes(data: &[u8]) -> Result<opencv::core::Mat, ImageMatchError> {
+    if data.is_empty() {
+        return Err(ImageMatchError::CanNotDecodeImage(0));
+    }
+
+    let buf = opencv::core::Vector::<u8>::from_slice(data);
+    let img = imgcodecs::imdecode(&buf, imgcodecs::IMREAD_COLOR)?;
+    if img.empty() {
+        return Err(ImageMatchError::CanNotDecodeImage(data.len()));
+    }
+    Ok(img)
+}
+
+/// 将图像转换为灰度（已经是单通道时直接复制）
+///
+/// 灰度匹配（`rgb = false`）时，匹配函数会把非单通道的源图像和模板转换为灰度。
+/// 用同一张彩色源图像匹配多个模板时，先调用本函数转换一次，再把灰度图传入，
+/// 可以避免每个模板都重复转换一次源图像
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{find_all_template, read_image, to_gray_mat};
+///
+/// let src = read_image("screenshot.png")?;
+/// let gray_src = to_gray_mat(&src)?;
+/// for path in ["a.png", "b.png", "c.png"] {
+///     let matches = find_all_template(&gray_src, &read_image(path)?, 0.8, false)?;
+/// }
+/// ```
+pub fn to_gray_mat(mat: &opencv::core::Mat) -> Result<opencv::core::Mat, ImageMatchError> {
+    if mat.channels() == 1 {
+        return Ok(mat.clone());
+    }
+
+    let code = if mat.channels() == 4 {
+        imgproc::COLOR_BGRA2GRAY
+    } else {
+        imgproc::COLOR_BGR2GRAY
+    };
+    let mut gray = opencv::core::Mat::default();
+    imgproc::cvt_color(mat, &mut gray, code, 0, DEFAULT_ALGORITHM_HINT)?;
+    Ok(gray)
+}
+
 /// 查找图片（优化版）- 返回布尔值
 ///
 /// # 参数
@@ -49,6 +114,7 @@ pub fn read_image(path: &str) -> Result<opencv::core::Mat, ImageMatchError> {
 /// }
 /// ```
 /// todo: 待优化，较python版本慢
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
 pub fn find_image_optimized(
     x: i32,
     y: i32,
@@ -59,13 +125,9 @@ pub fn find_image_optimized(
     rgb: bool,
 ) -> Result<bool, ImageMatchError> {
     // 读取模板（先读取，避免截图后等待）
-    let now = Instant::now();
     let template = read_image(image_path)?;
-    let cost = now.elapsed().as_micros();
-    println!("[find_image_optimized]读取模板{cost} 微秒");
 
     // 截图 - 根据模式选择最优路径
-    let now = Instant::now();
     let screenshot = if rgb {
         // 彩色模式：需要 BGR
         screenshot_to_mat(x as u32, y as u32, width, height)?
@@ -73,19 +135,29 @@ pub fn find_image_optimized(
         // 灰度模式：直接从 RGBA 转灰度，避免 BGR 中间转换
         screenshot_to_mat_gray(x as u32, y as u32, width, height)?
     };
-    let cost = now.elapsed().as_micros();
-    println!("[find_image_optimized]截图用时{cost} 微秒");
 
     // 匹配 - 只检查是否存在匹配，不需要提取所有结果
-    let now = Instant::now();
-    let found = find_template_exists(&screenshot, &template, threshold, rgb)?;
-    let cost = now.elapsed().as_micros();
-    println!("[find_image_optimized]匹配模板{cost} 微秒");
+    find_template_exists(&screenshot, &template, threshold, rgb, MatchMethod::CcoeffNormed)
+}
 
-    Ok(found)
+/// 将百分比形式的相似度（0-100）转换为匹配函数使用的阈值（0.0-1.0）
+///
+/// 结果会被限制在 [0, 1] 范围内，需要对越界输入报错时使用 `find_image_percent`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::threshold_from_percent;
+///
+/// assert_eq!(threshold_from_percent(80.0), 0.8);
+/// ```
+pub fn threshold_from_percent(p: f64) -> f64 {
+    (p / 100.0).clamp(0.0, 1.0)
 }
 
-/// 查找图片（坐标版优化版）- 返回第一个匹配的中心点坐标
+/// 查找图片（百分比阈值版）- 返回布尔值
+///
+/// 与 `find_image_optimized` 相同，但相似度以百分比（0-100）给出，
+/// 方便从以百分比表示置信度的工具迁移，避免把 80 当作 0.8 传入导致永远匹配不到
 ///
 /// # 参数
 /// - `x`: 截图区域左上角 X
@@ -93,278 +165,1556 @@ pub fn find_image_optimized(
 /// - `width`: 截图宽度
 /// - `height`: 截图高度
 /// - `image_path`: 模板图片路径
-/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `threshold_percent`: 相似度百分比 (例如 75 表示 0.75)
 /// - `rgb`: 是否使用彩色匹配 (默认 true)
 ///
 /// # 返回
-/// 如果找到匹配，返回绝对坐标 (中心点 x, 中心点 y)，否则返回 (0, 0)
+/// 如果找到匹配返回 true，否则返回 false；百分比不在 [0, 100] 内时返回 `InvalidThresholdPercent`
 ///
 /// # 示例
 /// ```rust
-/// use gjx_image_rs::find_image_optimized_coord;
+/// use image_utils::image_match::find_image_percent;
 ///
-/// let (x, y) = find_image_optimized_coord(100, 100, 800, 600, "template.png", 0.75, true)?;
-/// if x != 0 || y != 0 {
-///     println!("找到图片，中心点坐标: ({}, {})", x, y);
-/// }
+/// let found = find_image_percent(100, 100, 800, 600, "template.png", 80.0, true)?;
 /// ```
-/// todo: 待优化，较pyton版本慢
-pub fn find_image_optimized_coord(
+pub fn find_image_percent(
     x: i32,
     y: i32,
     width: u32,
     height: u32,
     image_path: &str,
+    threshold_percent: f64,
+    rgb: bool,
+) -> Result<bool, ImageMatchError> {
+    if !(0.0..=100.0).contains(&threshold_percent) {
+        return Err(ImageMatchError::InvalidThresholdPercent(threshold_percent));
+    }
+
+    find_image_optimized(x, y, width, height, image_path, threshold_from_percent(threshold_percent), rgb)
+}
+
+/// 在给定图像中查找图片 - 返回布尔值
+///
+/// 与 `find_image_optimized` 相同，但不截图，直接在传入的 Mat 中查找。
+/// 可以对同一帧用多个模板查找，也可以用固定图片做单元测试
+///
+/// # 参数
+/// - `src`: 源图像（OpenCV Mat，BGR 或灰度）
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 如果找到匹配返回 true，否则返回 false
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_in_mat;
+/// use image_utils::screenshot::screenshot_to_mat;
+///
+/// let frame = screenshot_to_mat(0, 0, 1920, 1080)?;
+/// let has_ok = find_image_in_mat(&frame, "ok.png", 0.8, true)?;
+/// let has_cancel = find_image_in_mat(&frame, "cancel.png", 0.8, true)?;
+/// ```
+pub fn find_image_in_mat(
+    src: &opencv::core::Mat,
+    image_path: &str,
     threshold: f64,
     rgb: bool,
-) -> Result<(i32, i32), ImageMatchError> {
-    // 读取模板（先读取，避免截图后等待）
+) -> Result<bool, ImageMatchError> {
     let template = read_image(image_path)?;
 
-    // 截图 - 根据模式选择最优路径
-    let screenshot = if rgb {
-        // 彩色模式：需要 BGR
-        screenshot_to_mat(x as u32, y as u32, width, height)?
-    } else {
-        // 灰度模式：直接从 RGBA 转灰度，避免 BGR 中间转换
-        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
-    };
+    find_template_exists(src, &template, threshold, rgb, MatchMethod::CcoeffNormed)
+}
 
-    // 使用与 find_images_optimized_coords 相同的方式：调用 find_all_template 获取所有匹配
-    // 然后取第一个（置信度最高的）匹配，确保坐标计算方式一致
-    let matches = find_all_template(&screenshot, &template, threshold, rgb)?;
-    
-    if let Some(first_match) = matches.first() {
-        // 使用与 find_images_optimized_coords 相同的坐标提取方式
-        let center_x = (*first_match.result.x()).round() as i32;
-        let center_y = (*first_match.result.y()).round() as i32;
-        Ok((x + center_x, y + center_y))
-    } else {
-        // 未找到匹配
-        Ok((0, 0))
-    }
+/// 在给定图像中查找图片 - 返回第一个匹配的中心点坐标
+///
+/// 与 `find_image_optimized_coord` 使用相同的匹配和坐标取整方式，但不截图
+///
+/// # 参数
+/// - `src`: 源图像（OpenCV Mat，BGR 或灰度）
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 如果找到匹配，返回相对于 `src` 的中心点坐标 (x, y)，否则返回 None
+pub fn find_image_in_mat_coord(
+    src: &opencv::core::Mat,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+) -> Result<Option<(i32, i32)>, ImageMatchError> {
+    let template = read_image(image_path)?;
+    let found = first_match_in_mat(src, &template, threshold, rgb, None)?;
+
+    Ok(found.map(|(center_x, center_y, _)| (center_x, center_y)))
 }
 
-/// 查找多图片（坐标版多目标）- 返回所有匹配的中心点坐标
+/// 在给定图像中查找内存中的模板 - 只返回是否找到
+///
+/// 与 `find_image_in_mat` 相同，但模板由 `read_image_from_bytes` 从字节数据解码
+///
+/// # 参数
+/// - `src`: 源图像（OpenCV Mat，BGR 或灰度）
+/// - `template_data`: 模板图像文件的字节内容
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 如果找到匹配返回 true，否则返回 false
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_in_mat_from_bytes;
+/// use image_utils::screenshot::screenshot_to_mat;
+///
+/// let frame = screenshot_to_mat(0, 0, 1920, 1080)?;
+/// let has_ok = find_image_in_mat_from_bytes(&frame, include_bytes!("../assets/ok.png"), 0.8, true)?;
+/// ```
+pub fn find_image_in_mat_from_bytes(
+    src: &opencv::core::Mat,
+    template_data: &[u8],
+    threshold: f64,
+    rgb: bool,
+) -> Result<bool, ImageMatchError> {
+    let template = read_image_from_bytes(template_data)?;
+
+    find_template_exists(src, &template, threshold, rgb, MatchMethod::CcoeffNormed)
+}
+
+/// 在给定图像中查找内存中的模板 - 返回第一个匹配的中心点坐标
+///
+/// 与 `find_image_in_mat_coord` 相同，但模板由 `read_image_from_bytes` 从字节数据解码
+///
+/// # 参数
+/// - `src`: 源图像（OpenCV Mat，BGR 或灰度）
+/// - `template_data`: 模板图像文件的字节内容
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 如果找到匹配，返回相对于 `src` 的中心点坐标 (x, y)，否则返回 None
+pub fn find_image_in_mat_coord_from_bytes(
+    src: &opencv::core::Mat,
+    template_data: &[u8],
+    threshold: f64,
+    rgb: bool,
+) -> Result<Option<(i32, i32)>, ImageMatchError> {
+    let template = read_image_from_bytes(template_data)?;
+    let found = first_match_in_mat(src, &template, threshold, rgb, None)?;
+
+    Ok(found.map(|(center_x, center_y, _)| (center_x, center_y)))
+}
+
+/// 查找图片（分级版）- 先缩小做粗略检查，可能命中时再做全分辨率匹配
+///
+/// 适用于"X 是否在屏幕上"且 X 大多数时候不在的轮询场景：
+/// 粗略检查在缩小后的图像上进行，代价约为全分辨率的 `coarse_scale²`，
+/// 只有粗略检查达到 `coarse_threshold` 时才执行全分辨率匹配
 ///
 /// # 参数
 /// - `x`: 截图区域左上角 X
 /// - `y`: 截图区域左上角 Y
 /// - `width`: 截图宽度
 /// - `height`: 截图高度
-/// - `image_paths`: 模板图片路径列表
-/// - `threshold`: 相似度阈值 (默认 0.75)
-/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 全分辨率匹配的相似度阈值
+/// - `rgb`: 是否使用彩色匹配
+/// - `coarse_scale`: 粗略检查的缩放比例，范围 (0, 1)，例如 0.5
+/// - `coarse_threshold`: 粗略检查的相似度阈值，缩小会损失细节，通常应低于 `threshold`
 ///
 /// # 返回
-/// 返回所有找到的匹配坐标列表，每个元素为 (中心点 x, 中心点 y)
+/// 如果找到匹配返回 true，否则返回 false
+///
+/// # 注意
+/// `coarse_scale` 不在 (0, 1) 范围内，或缩小后模板不足 1 像素时，跳过粗略检查直接全分辨率匹配
 ///
 /// # 示例
 /// ```rust
-/// use gjx_image_rs::find_images_optimized_coords;
+/// use image_utils::image_match::find_image_optimized_tiered;
 ///
-/// let paths = vec!["template1.png", "template2.png"];
-/// let coords = find_images_optimized_coords(100, 100, 800, 600, &paths, 0.75, true)?;
-/// for (x, y) in coords {
-///     println!("找到图片，中心点坐标: ({}, {})", x, y);
-/// }
+/// let found = find_image_optimized_tiered(0, 0, 2560, 1440, "popup.png", 0.85, false, 0.5, 0.7)?;
 /// ```
-/// todo: 待优化，较python慢
-pub fn find_images_optimized_coords(
+#[allow(clippy::too_many_arguments)]
+pub fn find_image_optimized_tiered(
     x: i32,
     y: i32,
     width: u32,
     height: u32,
-    image_paths: &[&str],
+    image_path: &str,
     threshold: f64,
     rgb: bool,
-) -> Result<Vec<(i32, i32)>, ImageMatchError> {
-    if image_paths.is_empty() {
-        return Ok(Vec::new());
-    }
+    coarse_scale: f64,
+    coarse_threshold: f64,
+) -> Result<bool, ImageMatchError> {
+    // 读取模板（先读取，避免截图后等待）
+    let template = read_image(image_path)?;
 
-    // 先截图一次，所有模板共享
+    // 截图 - 根据模式选择最优路径
     let screenshot = if rgb {
         screenshot_to_mat(x as u32, y as u32, width, height)?
     } else {
         screenshot_to_mat_gray(x as u32, y as u32, width, height)?
     };
 
-    let mut all_coords = Vec::new();
-
-    // 对每个模板进行匹配
-    for image_path in image_paths {
-        let template = read_image(image_path)?;
-        
-        // 获取模板尺寸，用于判断重叠
-        let template_size = template.size()?;
-        let template_w = template_size.width;
-        let template_h = template_size.height;
-        
-        // 查找所有匹配
-        let matches = find_all_template(&screenshot, &template, threshold, rgb)?;
-        
-        // 使用非极大值抑制（NMS）过滤重叠的匹配
-        // 由于 matches 已按置信度降序排序，我们遍历并只保留不重叠的匹配
-        let mut filtered_coords = Vec::new();
-        
-        // 使用模板尺寸作为最小距离阈值（如果两个匹配距离小于模板尺寸，认为是同一个）
-        let min_distance = template_w.max(template_h) as i32;
-        
-        for match_result in matches {
-            let center_x = (*match_result.result.x()).round() as i32;
-            let center_y = (*match_result.result.y()).round() as i32;
-            let abs_x = x + center_x;
-            let abs_y = y + center_y;
-            
-            // 检查是否与已有匹配重叠
-            let mut is_overlapping = false;
-            
-            for (existing_x, existing_y) in &filtered_coords {
-                // 使用曼哈顿距离判断重叠（更快）
-                let dx = i32::abs(abs_x - *existing_x);
-                let dy = i32::abs(abs_y - *existing_y);
-                
-                // 如果 X 和 Y 方向的距离都小于模板尺寸，认为是重叠
-                if dx < min_distance && dy < min_distance {
-                    is_overlapping = true;
-                    break;
-                }
-            }
-            
-            // 如果不重叠，添加到结果列表
-            if !is_overlapping {
-                filtered_coords.push((abs_x, abs_y));
-            }
+    // 粗略检查
+    let coarse_w = (template.cols() as f64 * coarse_scale).round();
+    let coarse_h = (template.rows() as f64 * coarse_scale).round();
+    if coarse_scale > 0.0 && coarse_scale < 1.0 && coarse_w >= 1.0 && coarse_h >= 1.0 {
+        let coarse_template = resize_mat(&template, coarse_scale)?;
+        let coarse_screenshot = resize_mat(&screenshot, coarse_scale)?;
+        if !find_template_exists(&coarse_screenshot, &coarse_template, coarse_threshold, rgb, MatchMethod::CcoeffNormed)? {
+            return Ok(false);
         }
-        
-        all_coords.extend(filtered_coords);
     }
 
-    Ok(all_coords)
+    // 全分辨率确认
+    find_template_exists(&screenshot, &template, threshold, rgb, MatchMethod::CcoeffNormed)
 }
 
-/// 查找所有模板匹配（兼容 aircv.find_all_template）
+/// 查找图片（坐标版优化版）- 返回第一个匹配的中心点坐标
 ///
 /// # 参数
-/// - `imgsrc`: 源图像（OpenCV Mat）
-/// - `imgobj`: 模板图像（OpenCV Mat）
-/// - `confidence`: 相似度阈值 (0.0-1.0)
-/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
 ///
 /// # 返回
-/// 匹配结果列表
+/// 如果找到匹配，返回绝对坐标 (中心点 x, 中心点 y)，否则返回 (0, 0)
 ///
 /// # 示例
 /// ```rust
-/// use gjx_image_rs::{imread, find_all_template};
-///
-/// let src = imread("screenshot.png")?;
-/// let template = imread("template.png")?;
-/// let results = find_all_template(&src, &template, 0.8, true)?;
+/// use gjx_image_rs::find_image_optimized_coord;
 ///
-/// for result in results {
-///     println!("找到匹配: 置信度={}, 中心点=({}, {})",
-///              result.confidence, result.result.0, result.result.1);
+/// let (x, y) = find_image_optimized_coord(100, 100, 800, 600, "template.png", 0.75, true)?;
+/// if x != 0 || y != 0 {
+///     println!("找到图片，中心点坐标: ({}, {})", x, y);
 /// }
 /// ```
-pub fn find_all_template(
-    imgsrc: &opencv::core::Mat,
-    imgobj: &opencv::core::Mat,
-    confidence: f64,
+/// todo: 待优化，较pyton版本慢
+pub fn find_image_optimized_coord(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
     rgb: bool,
-) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
-    let mut result_mat = opencv::core::Mat::default();
-
-    if rgb {
-        // 彩色模式直接匹配
-        imgproc::match_template(
-            imgsrc,
-            imgobj,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &opencv::core::Mat::default(),
-        )?;
-    } else {
-        // 灰度模式
-        // 如果源图像已经是灰度图（单通道），直接使用；否则转换
-        let gray_src = if imgsrc.channels() == 1 {
-            imgsrc.clone()
-        } else {
-            let mut gray = opencv::core::Mat::default();
-            imgproc::cvt_color(imgsrc, &mut gray, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
-            gray
-        };
-        
-        // 模板图像转换为灰度
-        let mut gray_obj = opencv::core::Mat::default();
-        imgproc::cvt_color(imgobj, &mut gray_obj, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
-
-        imgproc::match_template(
-            &gray_src,
-            &gray_obj,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &opencv::core::Mat::default(),
-        )?;
-    }
-
-    extract_matches(&result_mat, imgobj, confidence)
+) -> Result<(i32, i32), ImageMatchError> {
+    find_image_optimized_coord_blurred(x, y, width, height, image_path, threshold, rgb, None)
 }
 
-/// 检查模板是否存在（优化版，只返回布尔值，找到第一个匹配就返回）
+/// 查找图片（坐标版，可选匹配前高斯模糊）- 返回第一个匹配的中心点坐标
 ///
 /// # 参数
-/// - `imgsrc`: 源图像（OpenCV Mat）
-/// - `imgobj`: 模板图像（OpenCV Mat）
-/// - `confidence`: 相似度阈值 (0.0-1.0)
-/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `blur`: 高斯核尺寸，None 时与 `find_image_optimized_coord` 完全一致
 ///
 /// # 返回
-/// 如果找到匹配返回 true，否则返回 false
-fn find_template_exists(
-    imgsrc: &opencv::core::Mat,
-    imgobj: &opencv::core::Mat,
-    confidence: f64,
+/// 如果找到匹配，返回绝对坐标 (中心点 x, 中心点 y)，否则返回 (0, 0)
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_optimized_coord_blurred;
+///
+/// // 对抗锯齿文字使用 3x3 模糊
+/// let (x, y) = find_image_optimized_coord_blurred(100, 100, 800, 600, "text.png", 0.8, false, Some(3))?;
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn find_image_optimized_coord_blurred(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
     rgb: bool,
-) -> Result<bool, ImageMatchError> {
-    let mut result_mat = opencv::core::Mat::default();
+    blur: Option<i32>,
+) -> Result<(i32, i32), ImageMatchError> {
+    let found = find_first_match(x, y, width, height, image_path, threshold, rgb, blur)?;
+
+    // 未找到匹配时返回 (0, 0)
+    Ok(found.map_or((0, 0), |(center_x, center_y, _)| (center_x, center_y)))
+}
+
+/// 查找图片（坐标 + 置信度版）- 返回第一个匹配的中心点坐标及其置信度
+///
+/// 与 `find_image_optimized_coord` 使用相同的匹配和坐标取整方式，
+/// 但未找到时返回 None，而不是与屏幕原点 (0, 0) 混淆的哨兵值
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 如果找到匹配，返回 (中心点 x, 中心点 y, 置信度)，坐标为绝对坐标，否则返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_optimized_coord_conf;
+///
+/// if let Some((x, y, confidence)) = find_image_optimized_coord_conf(0, 0, 1920, 1080, "template.png", 0.75, true)? {
+///     println!("找到图片 ({}, {})，置信度 {:.3}", x, y, confidence);
+/// }
+/// ```
+pub fn find_image_optimized_coord_conf(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+) -> Result<Option<(i32, i32, f64)>, ImageMatchError> {
+    find_first_match(x, y, width, height, image_path, threshold, rgb, None)
+}
+
+/// 等待图片出现 - 按固定间隔重复截图和匹配，直到找到或超时
+///
+/// 模板只在开始前读取一次，每次轮询都会重新截图
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `timeout`: 最长等待时间，至少会尝试一次
+/// - `interval`: 两次尝试之间的间隔
+///
+/// # 返回
+/// 找到时返回 Some(绝对中心点坐标)，超时返回 None
+///
+/// # 示例
+/// ```rust
+/// use std::time::Duration;
+/// use image_utils::image_match::wait_for_image;
+///
+/// let found = wait_for_image(
+///     0, 0, 1920, 1080, "loading_done.png", 0.8, true,
+///     Duration::from_secs(10),
+///     Duration::from_millis(200),
+/// )?;
+/// match found {
+///     Some((x, y)) => println!("加载完成: ({}, {})", x, y),
+///     None => println!("等待超时"),
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn wait_for_image(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<Option<(i32, i32)>, ImageMatchError> {
+    // 模板只读取一次，灰度模式下也只转换一次
+    let template = read_image(image_path)?;
+    let template = if rgb { template } else { to_gray_mat(&template)? };
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        // 每次都重新截图
+        let screenshot = if rgb {
+            screenshot_to_mat(x as u32, y as u32, width, height)?
+        } else {
+            screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+        };
+
+        let matches = find_all_template(&screenshot, &template, threshold, rgb)?;
+        if let Some(first_match) = matches.first() {
+            let center = first_match.result.round_to_i32();
+            return Ok(Some((x + center.x(), y + center.y())));
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        thread::sleep(interval.min(remaining));
+    }
+}
+
+/// 截图并查找置信度最高的匹配，返回 (绝对中心点 x, 绝对中心点 y, 置信度)
+#[allow(clippy::too_many_arguments)]
+fn find_first_match(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+    blur: Option<i32>,
+) -> Result<Option<(i32, i32, f64)>, ImageMatchError> {
+    // 读取模板（先读取，避免截图后等待）
+    let template = read_image(image_path)?;
+
+    // 截图 - 根据模式选择最优路径
+    let screenshot = if rgb {
+        // 彩色模式：需要 BGR
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        // 灰度模式：直接从 RGBA 转灰度，避免 BGR 中间转换
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+
+    let found = first_match_in_mat(&screenshot, &template, threshold, rgb, blur)?;
+
+    Ok(found.map(|(center_x, center_y, confidence)| (x + center_x, y + center_y, confidence)))
+}
+
+/// 在给定图像中查找置信度最高的匹配，返回 (相对中心点 x, 相对中心点 y, 置信度)
+fn first_match_in_mat(
+    src: &opencv::core::Mat,
+    template: &opencv::core::Mat,
+    threshold: f64,
+    rgb: bool,
+    blur: Option<i32>,
+) -> Result<Option<(i32, i32, f64)>, ImageMatchError> {
+    // 使用与 find_images_optimized_coords 相同的方式：调用 find_all_template 获取所有匹配
+    // 然后取第一个（置信度最高的）匹配，确保坐标计算方式一致
+    let matches = find_all_template_blurred(src, template, threshold, rgb, blur)?;
+
+    Ok(matches.first().map(|first_match| {
+        // 使用与 find_images_optimized_coords 相同的坐标提取方式
+        let center = first_match.result.round_to_i32();
+        (*center.x(), *center.y(), first_match.confidence)
+    }))
+}
+
+/// 查找多图片（坐标版多目标）- 返回所有匹配的中心点坐标
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_paths`: 模板图片路径列表
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 返回所有找到的匹配坐标列表，每个元素为 (中心点 x, 中心点 y)
+///
+/// # 示例
+/// ```rust
+/// use gjx_image_rs::find_images_optimized_coords;
+///
+/// let paths = vec!["template1.png", "template2.png"];
+/// let coords = find_images_optimized_coords(100, 100, 800, 600, &paths, 0.75, true)?;
+/// for (x, y) in coords {
+///     println!("找到图片，中心点坐标: ({}, {})", x, y);
+/// }
+/// ```
+/// todo: 待优化，较python慢
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_images_optimized_coords(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_paths: &[&str],
+    threshold: f64,
+    rgb: bool,
+) -> Result<Vec<(i32, i32)>, ImageMatchError> {
+    let labeled = find_images_labeled_inner(
+        x, y, width, height, image_paths, threshold, rgb, DEFAULT_NMS_IOU_THRESHOLD, None, read_image_shared,
+    )?;
+    Ok(labeled_to_coords(labeled))
+}
+
+/// 查找多图片（带标签）- 返回每个匹配来自哪个模板
+///
+/// 与 `find_images_optimized_coords` 相同，但每个结果额外带上模板路径和置信度，
+/// 便于同时查找多个按钮（如"播放"、"暂停"、"停止"）后根据出现的是哪一个做分支
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_paths`: 模板图片路径列表
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 所有匹配的 (模板路径, 中心点 x, 中心点 y, 置信度)，按模板顺序排列，同一模板内按置信度降序
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_images_labeled;
+///
+/// let paths = ["play.png", "pause.png", "stop.png"];
+/// for (path, x, y, confidence) in find_images_labeled(0, 0, 1920, 1080, &paths, 0.8, true)? {
+///     match path.as_str() {
+///         "play.png" => println!("播放按钮在 ({}, {})，置信度 {:.2}", x, y, confidence),
+///         "pause.png" => println!("暂停按钮在 ({}, {})", x, y),
+///         _ => println!("其他按钮在 ({}, {})", x, y),
+///     }
+/// }
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_images_labeled(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_paths: &[&str],
+    threshold: f64,
+    rgb: bool,
+) -> Result<Vec<(String, i32, i32, f64)>, ImageMatchError> {
+    let labeled = find_images_labeled_inner(
+        x, y, width, height, image_paths, threshold, rgb, DEFAULT_NMS_IOU_THRESHOLD, None, read_image_shared,
+    )?;
+
+    Ok(labeled
+        .into_iter()
+        .map(|(path, x, y, confidence)| (path.to_string(), x, y, confidence))
+        .collect())
+}
+
+/// 查找单个模板的所有出现位置 - 返回去重后的中心点坐标
+///
+/// 适用于统计网格中相同物品的数量等场景。
+/// 同一目标周围的重复匹配会按 `DEFAULT_NMS_IOU_THRESHOLD` 做非极大值抑制去除
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 所有匹配的中心点绝对坐标，按置信度降序排列
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_all_coords;
+///
+/// let coords = find_all_coords(0, 0, 1920, 1080, "potion.png", 0.8, true)?;
+/// println!("背包中有 {} 瓶药水", coords.len());
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_all_coords(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+) -> Result<Vec<(i32, i32)>, ImageMatchError> {
+    let labeled = find_images_labeled_inner(
+        x, y, width, height, &[image_path], threshold, rgb, DEFAULT_NMS_IOU_THRESHOLD, None,
+        read_image_shared,
+    )?;
+    Ok(labeled_to_coords(labeled))
+}
+
+/// 非极大值抑制的默认 IoU 阈值
+///
+/// 同一目标周围几个像素内的重复匹配彼此 IoU 很高，会被去除；
+/// 并排摆放的相同图标互不重叠（IoU 为 0），都会被保留
+pub const DEFAULT_NMS_IOU_THRESHOLD: f64 = 0.3;
+
+/// 查找多图片（坐标版多目标，指定 NMS 的 IoU 阈值）
+///
+/// 与 `find_images_optimized_coords` 相同，但可以指定去除重复匹配时使用的 IoU 阈值：
+/// 与已保留的匹配交并比超过 `iou_threshold` 的匹配会被丢弃。
+/// 目标之间允许部分重叠时可以调高该值
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_paths`: 模板图片路径列表
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `iou_threshold`: 非极大值抑制的 IoU 阈值，范围 [0, 1]（默认 `DEFAULT_NMS_IOU_THRESHOLD`）
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_images_optimized_coords_iou;
+///
+/// let coords = find_images_optimized_coords_iou(0, 0, 1920, 1080, &["slot.png"], 0.8, true, 0.5)?;
+/// ```
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", ret, err))]
+pub fn find_images_optimized_coords_iou(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_paths: &[&str],
+    threshold: f64,
+    rgb: bool,
+    iou_threshold: f64,
+) -> Result<Vec<(i32, i32)>, ImageMatchError> {
+    let labeled = find_images_labeled_inner(
+        x, y, width, height, image_paths, threshold, rgb, iou_threshold, None, read_image_shared,
+    )?;
+    Ok(labeled_to_coords(labeled))
+}
+
+/// 非极大值抑制：按置信度从高到低保留匹配，丢弃与已保留匹配的 IoU 超过 `iou_threshold` 的匹配
+///
+/// # 参数
+/// - `matches`: 匹配结果列表（顺序任意，置信度按值越大越相似解释）
+/// - `iou_threshold`: IoU 阈值，范围 [0, 1]
+///
+/// # 返回
+/// 保留的匹配结果，按置信度降序排列
+pub fn non_max_suppression(mut matches: Vec<MatchResult<i32>>, iou_threshold: f64) -> Vec<MatchResult<i32>> {
+    matches.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut kept: Vec<MatchResult<i32>> = Vec::new();
+    for match_result in matches {
+        if kept.iter().all(|existing| existing.iou(&match_result) <= iou_threshold) {
+            kept.push(match_result);
+        }
+    }
+    kept
+}
+
+/// 查找多图片（坐标版多目标，可取消）
+///
+/// 与 `find_images_optimized_coords` 相同，但在每个模板之间以及遍历结果矩阵时检查 `cancel`，
+/// 被设置后尽快返回 `ImageMatchError::Cancelled`。
+/// 需要超时时，可由计时线程在超时后设置该标志
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_paths`: 模板图片路径列表
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `cancel`: 取消标志
+///
+/// # 示例
+/// ```rust
+/// use std::sync::atomic::AtomicBool;
+/// use image_utils::image_match::find_images_optimized_coords_cancellable;
+///
+/// let cancel = AtomicBool::new(false);
+/// let paths = vec!["template1.png", "template2.png"];
+/// let coords = find_images_optimized_coords_cancellable(0, 0, 1920, 1080, &paths, 0.75, true, &cancel)?;
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn find_images_optimized_coords_cancellable(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_paths: &[&str],
+    threshold: f64,
+    rgb: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<(i32, i32)>, ImageMatchError> {
+    let labeled = find_images_labeled_inner(
+        x, y, width, height, image_paths, threshold, rgb, DEFAULT_NMS_IOU_THRESHOLD, Some(cancel),
+        read_image_shared,
+    )?;
+    Ok(labeled_to_coords(labeled))
+}
+
+/// `find_images_optimized_coords` 系列的公共实现，模板由 `load_template` 提供（读取文件或从缓存获取）
+///
+/// 截图一次后，各模板的匹配和 NMS 在 rayon 线程池中并行执行
+///
+/// 返回 (模板路径, 中心点 x, 中心点 y, 置信度)，按模板顺序排列，同一模板内按置信度降序
+#[allow(clippy::too_many_arguments)]
+fn find_images_labeled_inner<'a>(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_paths: &[&'a str],
+    threshold: f64,
+    rgb: bool,
+    iou_threshold: f64,
+    cancel: Option<&AtomicBool>,
+    load_template: impl Fn(&str) -> Result<Arc<opencv::core::Mat>, ImageMatchError> + Sync,
+) -> Result<Vec<(&'a str, i32, i32, f64)>, ImageMatchError> {
+    if image_paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // 先截图一次，所有模板共享
+    let screenshot = if rgb {
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+    let screenshot_arc = Arc::new(screenshot);
+
+    // 各模板并行匹配，collect 保持模板顺序
+    let per_template = image_paths
+        .par_iter()
+        .map(|&image_path| {
+            check_cancelled(cancel)?;
+
+            // 灰度模式下截图已经是单通道，模板也在这里转换一次，匹配时不再转换
+            let template = load_template(image_path)?;
+            let template = if rgb || template.channels() == 1 {
+                template
+            } else {
+                Arc::new(to_gray_mat(&template)?)
+            };
+
+            // 查找所有匹配
+            let result_mat = run_match(&screenshot_arc, &template, rgb, None)?;
+            let matches =
+                extract_matches_inner(&result_mat, &template, threshold, MatchMethod::CcoeffNormed, cancel)?;
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                image_path,
+                matches = matches.len(),
+                best_confidence = matches.first().map(|m| m.confidence),
+                "模板匹配完成"
+            );
+
+            // 使用非极大值抑制（NMS）过滤同一目标周围的重复匹配
+            Ok(non_max_suppression(matches, iou_threshold)
+                .into_iter()
+                .map(|match_result| {
+                    let center = match_result.result.round_to_i32();
+                    (image_path, x + center.x(), y + center.y(), match_result.confidence)
+                })
+                .collect::<Vec<_>>())
+        })
+        .collect::<Result<Vec<_>, ImageMatchError>>()?;
+
+    Ok(per_template.into_iter().flatten().collect())
+}
+
+/// 读取模板并包装为 `Arc`，作为 `find_images_labeled_inner` 不使用缓存时的 `load_template`
+fn read_image_shared(path: &str) -> Result<Arc<opencv::core::Mat>, ImageMatchError> {
+    read_image(path).map(Arc::new)
+}
+
+/// 去掉 `find_images_labeled_inner` 结果中的模板路径和置信度，只保留坐标
+fn labeled_to_coords(labeled: Vec<(&str, i32, i32, f64)>) -> Vec<(i32, i32)> {
+    labeled.into_iter().map(|(_, x, y, _)| (x, y)).collect()
+}
+
+/// 模板缓存 - 按路径缓存已读取的模板，避免重复读取磁盘
+///
+/// 模板在第一次使用时读取，之后返回同一个 `Arc<Mat>`，不会复制像素数据
+/// （opencv 的 `Mat::clone` 是深拷贝）。
+/// 灰度匹配时使用的灰度模板也会在第一次使用时转换并缓存，之后不再重复转换。
+/// 模板文件在磁盘上被修改后，可以用 `invalidate` 或 `clear` 使其重新读取
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::TemplateCache;
+///
+/// let cache = TemplateCache::new();
+/// let paths = ["template1.png", "template2.png"];
+/// loop {
+///     let coords = cache.find_coords(0, 0, 1920, 1080, &paths, 0.75, true)?;
+///     // ...
+/// }
+/// ```
+#[derive(Default)]
+pub struct TemplateCache {
+    templates: Mutex<HashMap<String, CachedTemplate>>,
+}
+
+/// 缓存的模板：彩色原图，以及按需转换的灰度版本
+struct CachedTemplate {
+    color: Arc<opencv::core::Mat>,
+    gray: Option<Arc<opencv::core::Mat>>,
+}
+
+impl TemplateCache {
+    /// 创建空的模板缓存
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取模板，未缓存时从磁盘读取并缓存
+    ///
+    /// # 参数
+    /// - `path`: 模板图片路径
+    pub fn get(&self, path: &str) -> Result<Arc<opencv::core::Mat>, ImageMatchError> {
+        let mut templates = self.lock();
+        if let Some(template) = templates.get(path) {
+            return Ok(Arc::clone(&template.color));
+        }
+
+        let template = read_image_shared(path)?;
+        templates.insert(path.to_string(), CachedTemplate { color: Arc::clone(&template), gray: None });
+        Ok(template)
+    }
+
+    /// 获取灰度模板，未缓存时读取并转换为灰度后缓存
+    ///
+    /// # 参数
+    /// - `path`: 模板图片路径
+    pub fn get_gray(&self, path: &str) -> Result<Arc<opencv::core::Mat>, ImageMatchError> {
+        let mut templates = self.lock();
+        if let Some(template) = templates.get_mut(path) {
+            // 彩色模板已缓存：只在第一次需要灰度时转换
+            if let Some(gray) = &template.gray {
+                return Ok(Arc::clone(gray));
+            }
+            let gray = Arc::new(to_gray_mat(&template.color)?);
+            template.gray = Some(Arc::clone(&gray));
+            return Ok(gray);
+        }
+
+        let color = read_image_shared(path)?;
+        let gray = Arc::new(to_gray_mat(&color)?);
+        templates.insert(path.to_string(), CachedTemplate { color, gray: Some(Arc::clone(&gray)) });
+        Ok(gray)
+    }
+
+    /// 清空所有缓存的模板
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// 移除指定路径的缓存，下次使用时重新读取
+    ///
+    /// # 返回
+    /// 该路径之前是否已缓存
+    pub fn invalidate(&self, path: &str) -> bool {
+        self.lock().remove(path).is_some()
+    }
+
+    /// 已缓存的模板数量
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// 是否没有缓存任何模板
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// 查找多图片（坐标版多目标），使用缓存的模板
+    ///
+    /// 与 `find_images_optimized_coords` 行为一致，只是模板从缓存获取
+    ///
+    /// # 参数
+    /// - `x`: 截图区域左上角 X
+    /// - `y`: 截图区域左上角 Y
+    /// - `width`: 截图宽度
+    /// - `height`: 截图高度
+    /// - `image_paths`: 模板图片路径列表
+    /// - `threshold`: 相似度阈值 (默认 0.75)
+    /// - `rgb`: 是否使用彩色匹配 (默认 true)
+    ///
+    /// # 返回
+    /// 返回所有找到的匹配坐标列表，每个元素为 (中心点 x, 中心点 y)
+    #[allow(clippy::too_many_arguments)]
+    pub fn find_coords(
+        &self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        image_paths: &[&str],
+        threshold: f64,
+        rgb: bool,
+    ) -> Result<Vec<(i32, i32)>, ImageMatchError> {
+        // 灰度模式使用缓存的灰度模板，避免每次调用都重新转换
+        let load_template = |path: &str| if rgb { self.get(path) } else { self.get_gray(path) };
+        let labeled = find_images_labeled_inner(
+            x, y, width, height, image_paths, threshold, rgb, DEFAULT_NMS_IOU_THRESHOLD, None, load_template,
+        )?;
+        Ok(labeled_to_coords(labeled))
+    }
+
+    /// 获取缓存的锁，锁被毒化（持有锁的线程 panic）时继续使用其中的数据
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, CachedTemplate>> {
+        self.templates.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// 查找多图片中最佳的一个 - 返回所有模板中置信度最高的匹配
+///
+/// 适用于同一个界面元素有多个候选模板（不同主题/状态）的情况，
+/// 只关心"任意一个变体出现"以及最像的是哪一个。
+/// 与 `find_images_optimized_coords`（返回所有模板的所有匹配）不同，这里只返回一个结果
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_paths`: 模板图片路径列表
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 如果找到，返回 (模板在 `image_paths` 中的下标, 匹配结果)，匹配结果中的坐标为屏幕绝对坐标；
+/// 所有模板都未达到阈值时返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_best_of_templates;
+///
+/// let paths = ["button_light.png", "button_dark.png"];
+/// if let Some((index, result)) = find_best_of_templates(0, 0, 1920, 1080, &paths, 0.8, true)? {
+///     println!("匹配到 {}，置信度 {}", paths[index], result.confidence);
+/// }
+/// ```
+pub fn find_best_of_templates(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_paths: &[&str],
+    threshold: f64,
+    rgb: bool,
+) -> Result<Option<(usize, MatchResult<i32>)>, ImageMatchError> {
+    if image_paths.is_empty() {
+        return Ok(None);
+    }
+
+    // 先截图一次，所有模板共享
+    let screenshot = if rgb {
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+
+    let mut best: Option<(usize, MatchResult<i32>)> = None;
+
+    for (index, image_path) in image_paths.iter().enumerate() {
+        let template = read_image(image_path)?;
+
+        // 结果已按置信度降序排序，第一个即为该模板的最佳匹配
+        let matches = find_all_template(&screenshot, &template, threshold, rgb)?;
+        if let Some(candidate) = matches.into_iter().next() {
+            let is_better = best
+                .as_ref()
+                .is_none_or(|(_, current)| candidate.confidence > current.confidence);
+            if is_better {
+                best = Some((index, candidate));
+            }
+        }
+    }
+
+    Ok(best.map(|(index, result)| (index, offset_match_result(&result, x, y))))
+}
+
+/// 查找图片（两阶段确认）- 返回通过严格阈值复核的中心点坐标
+///
+/// 先用宽松阈值定位候选位置，再重新截取候选位置处模板大小的区域，
+/// 用 `confidence_at` 在这张新截图上复核，只有达到严格阈值才返回。
+/// 复核使用的是另一帧画面，单帧的噪声、动画或闪烁造成的误报会被过滤掉
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `loose_threshold`: 定位候选时使用的宽松阈值
+/// - `strict_threshold`: 复核时使用的严格阈值
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+///
+/// # 返回
+/// 通过复核时返回绝对坐标 (中心点 x, 中心点 y)，否则返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_confirmed;
+///
+/// if let Some((x, y)) = find_image_confirmed(0, 0, 1920, 1080, "button.png", 0.7, 0.9, true)? {
+///     println!("确认找到按钮: ({}, {})", x, y);
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn find_image_confirmed(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    loose_threshold: f64,
+    strict_threshold: f64,
+    rgb: bool,
+) -> Result<Option<(i32, i32)>, ImageMatchError> {
+    let template = read_image(image_path)?;
+
+    let screenshot = if rgb {
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+
+    // 第一阶段：宽松阈值定位，取置信度最高的候选
+    let matches = find_all_template(&screenshot, &template, loose_threshold, rgb)?;
+    let Some(candidate) = matches.first() else {
+        return Ok(None);
+    };
+
+    // 第二阶段：重新截取候选位置，在新的一帧上复核
+    let top_left = candidate.top_left();
+    let (roi_x, roi_y) = ((x + top_left.x()) as u32, (y + top_left.y()) as u32);
+    let (roi_w, roi_h) = (candidate.width() as u32, candidate.height() as u32);
+    let roi = if rgb {
+        screenshot_to_mat(roi_x, roi_y, roi_w, roi_h)?
+    } else {
+        screenshot_to_mat_gray(roi_x, roi_y, roi_w, roi_h)?
+    };
+
+    let confidence = confidence_at(&roi, &template, (0, 0), rgb)?;
+    if confidence < strict_threshold {
+        return Ok(None);
+    }
+
+    let center = candidate.result.round_to_i32();
+    Ok(Some((x + center.x(), y + center.y())))
+}
+
+/// 查找图片，忽略中心点落在排除区域内的匹配
+///
+/// 适用于"逐个处理每个实例"的循环：已经处理过的元素仍留在屏幕上时，
+/// 把它们的区域传入 `exclude`，就能在同一截图区域内继续找下一个
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `exclude`: 排除区域列表，每个元素为屏幕绝对坐标 (左上 x, 左上 y, 宽, 高)
+///
+/// # 返回
+/// 不在排除区域内、置信度最高的匹配（屏幕绝对坐标），没有时返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_excluding;
+///
+/// let mut handled = Vec::new();
+/// while let Some(result) = find_image_excluding(0, 0, 1920, 1080, "item.png", 0.8, true, &handled)? {
+///     let top_left = result.top_left();
+///     handled.push((*top_left.x(), *top_left.y(), result.width(), result.height()));
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn find_image_excluding(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+    exclude: &[(i32, i32, i32, i32)],
+) -> Result<Option<MatchResult<i32>>, ImageMatchError> {
+    let template = read_image(image_path)?;
+
+    let screenshot = if rgb {
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+
+    // 结果已按置信度降序排序，返回第一个不在排除区域内的匹配
+    let matches = find_all_template(&screenshot, &template, threshold, rgb)?;
+    let found = matches
+        .iter()
+        .map(|m| offset_match_result(m, x, y))
+        .find(|m| {
+            let (center_x, center_y) = (*m.result.x(), *m.result.y());
+            !exclude.iter().any(|&(ex, ey, ew, eh)| {
+                center_x >= ex as f64
+                    && center_x < (ex + ew) as f64
+                    && center_y >= ey as f64
+                    && center_y < (ey + eh) as f64
+            })
+        });
+
+    Ok(found)
+}
+
+/// 多尺度查找图片 - 在多个缩放比例下匹配模板，返回全局最佳匹配
+///
+/// 适用于界面在不同 DPI 缩放（如 100%、125%、150%）下渲染的情况：
+/// 模板按每个比例缩放后分别匹配，取所有比例中置信度最高的结果，而不是第一个达到阈值的比例
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `threshold`: 相似度阈值 (默认 0.75)
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `scales`: 模板缩放比例列表，如 `[1.0, 1.25, 1.5]`；
+///   缩放后模板大于截图区域、不足 1 像素或比例不为正数的会被跳过
+///
+/// # 返回
+/// (匹配结果, 匹配时的缩放比例)，匹配结果中的坐标为屏幕绝对坐标；
+/// 所有比例都未达到阈值时返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_multiscale;
+///
+/// let scales = [1.0, 1.25, 1.5];
+/// if let Some((result, scale)) = find_image_multiscale(0, 0, 1920, 1080, "icon.png", 0.8, true, &scales)? {
+///     println!("在 {} 倍下找到，中心点 ({}, {})", scale, result.result.x(), result.result.y());
+/// }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn find_image_multiscale(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+    scales: &[f64],
+) -> Result<Option<(MatchResult<i32>, f64)>, ImageMatchError> {
+    // 灰度模式下先转换模板，避免每个缩放比例都重复转换
+    let template = read_image(image_path)?;
+    let template = if rgb { template } else { to_gray_mat(&template)? };
+
+    let screenshot = if rgb {
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+
+    let screenshot_size = screenshot.size()?;
+    let template_size = template.size()?;
+
+    let mut best: Option<(MatchResult<i32>, f64)> = None;
+
+    for &scale in scales {
+        if scale.is_nan() || scale <= 0.0 {
+            continue;
+        }
+
+        // 先计算缩放后的尺寸，跳过过大或过小的比例
+        let scaled_w = (template_size.width as f64 * scale).round() as i32;
+        let scaled_h = (template_size.height as f64 * scale).round() as i32;
+        if scaled_w < 1
+            || scaled_h < 1
+            || scaled_w > screenshot_size.width
+            || scaled_h > screenshot_size.height
+        {
+            continue;
+        }
+
+        let scaled_template = if scale == 1.0 {
+            template.clone()
+        } else {
+            resize_mat(&template, scale)?
+        };
+
+        // 结果已按置信度降序排序，第一个即为该比例的最佳匹配
+        let matches = find_all_template(&screenshot, &scaled_template, threshold, rgb)?;
+        if let Some(candidate) = matches.into_iter().next() {
+            let is_better = best
+                .as_ref()
+                .is_none_or(|(current, _)| candidate.confidence > current.confidence);
+            if is_better {
+                best = Some((candidate, scale));
+            }
+        }
+    }
+
+    Ok(best.map(|(result, scale)| (offset_match_result(&result, x, y), scale)))
+}
+
+/// 查找带透明通道的图片 - 以模板的 Alpha 通道作为掩码匹配
+///
+/// `read_image` 使用 `IMREAD_COLOR` 会丢弃透明通道，透明区域下的背景会影响匹配分数。
+/// 这里以 `IMREAD_UNCHANGED` 读取模板，将 Alpha 通道作为掩码传给 `match_template`，
+/// 只比较不透明的像素；模板没有 Alpha 通道时退化为普通匹配
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径（通常为带透明通道的 PNG）
+/// - `threshold`: 匹配阈值，含义取决于 `method`（见 `find_all_template_with_method`）
+/// - `rgb`: 是否使用彩色匹配 (默认 true)
+/// - `method`: 匹配方法，只支持 `CcorrNormed` 和 `SqdiffNormed`
+///
+/// # 返回
+/// 最佳匹配（屏幕绝对坐标），未找到返回 None；
+/// 方法为 `CcoeffNormed` 时返回 `ImageMatchError::MaskUnsupported`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_image_with_mask;
+/// use image_utils::types::MatchMethod;
+///
+/// let found = find_image_with_mask(0, 0, 1920, 1080, "icon.png", 0.95, true, MatchMethod::CcorrNormed)?;
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn find_image_with_mask(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    threshold: f64,
+    rgb: bool,
+    method: MatchMethod,
+) -> Result<Option<MatchResult<i32>>, ImageMatchError> {
+    if method == MatchMethod::CcoeffNormed {
+        return Err(ImageMatchError::MaskUnsupported(method));
+    }
+
+    let raw = imgcodecs::imread(image_path, imgcodecs::IMREAD_UNCHANGED)?;
+    if raw.empty() {
+        return Err(ImageMatchError::CanNotReadImage(image_path.to_string()));
+    }
+
+    let screenshot = if rgb {
+        screenshot_to_mat(x as u32, y as u32, width, height)?
+    } else {
+        screenshot_to_mat_gray(x as u32, y as u32, width, height)?
+    };
+
+    let matches = if raw.channels() == 4 {
+        // 拆分出 Alpha 通道作为掩码
+        let mut mask = opencv::core::Mat::default();
+        opencv::core::extract_channel(&raw, &mut mask, 3)?;
+
+        let code = if rgb { imgproc::COLOR_BGRA2BGR } else { imgproc::COLOR_BGRA2GRAY };
+        let mut template = opencv::core::Mat::default();
+        imgproc::cvt_color(&raw, &mut template, code, 0, DEFAULT_ALGORITHM_HINT)?;
+
+        let mut gray_src = opencv::core::Mat::default();
+        let src = if rgb || screenshot.channels() == 1 {
+            &screenshot
+        } else {
+            imgproc::cvt_color(&screenshot, &mut gray_src, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
+            &gray_src
+        };
+
+        check_template_fits(src, &template)?;
+        let mut result_mat = opencv::core::Mat::default();
+        imgproc::match_template(src, &template, &mut result_mat, method.to_cv(), &mask)?;
+
+        extract_matches_inner(&result_mat, &template, threshold, method, None)?
+    } else {
+        // 没有透明通道，按普通方式匹配
+        let template = if raw.channels() == 3 {
+            raw
+        } else {
+            read_image(image_path)?
+        };
+        find_all_template_with_method(&screenshot, &template, threshold, rgb, method)?
+    };
+
+    Ok(matches.first().map(|m| offset_match_result(m, x, y)))
+}
+
+/// 查找所有模板匹配（兼容 aircv.find_all_template）
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+///
+/// # 返回
+/// 匹配结果列表
+///
+/// # 示例
+/// ```rust
+/// use gjx_image_rs::{imread, find_all_template};
+///
+/// let src = imread("screenshot.png")?;
+/// let template = imread("template.png")?;
+/// let results = find_all_template(&src, &template, 0.8, true)?;
+///
+/// for result in results {
+///     println!("找到匹配: 置信度={}, 中心点=({}, {})",
+///              result.confidence, result.result.0, result.result.1);
+/// }
+/// ```
+pub fn find_all_template(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    find_all_template_blurred(imgsrc, imgobj, confidence, rgb, None)
+}
+
+/// 查找所有模板匹配（可选匹配前高斯模糊）
+///
+/// 清晰的模板与屏幕上抗锯齿渲染的文字/图标匹配时，边缘像素的差异会拉低置信度。
+/// 对源图像和模板做同样的轻度模糊可以抹平亚像素渲染差异，通常能提高置信度
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `blur`: 高斯核尺寸（如 `Some(3)`），None 或小于等于 1 时不模糊，偶数会向上取为奇数
+///
+/// # 返回
+/// 匹配结果列表（按置信度降序）
+pub fn find_all_template_blurred(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    blur: Option<i32>,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    let result_mat = run_match(imgsrc, imgobj, rgb, blur)?;
+
+    extract_matches(&result_mat, imgobj, confidence)
+}
+
+/// 查找所有模板匹配（指定匹配方法）
+///
+/// 与 `find_all_template` 相同，但可以选择 OpenCV 的匹配方法。
+/// `CcoeffNormed`、`CcorrNormed` 值越大越相似，`confidence` 是下限；
+/// `SqdiffNormed` 值越小越相似，`confidence` 是上限（如 0.05）
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 匹配阈值，含义取决于 `method`
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `method`: 匹配方法
+///
+/// # 返回
+/// 匹配结果列表（按相似程度从高到低排序），`confidence` 字段为该方法的原始匹配值
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{read_image, find_all_template_with_method};
+/// use image_utils::types::MatchMethod;
+///
+/// let src = read_image("screenshot.png")?;
+/// let template = read_image("flat_button.png")?;
+/// let results = find_all_template_with_method(&src, &template, 0.05, true, MatchMethod::SqdiffNormed)?;
+/// ```
+pub fn find_all_template_with_method(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    method: MatchMethod,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    let result_mat = run_match_with_method(imgsrc, imgobj, rgb, None, method)?;
+
+    extract_matches_inner(&result_mat, imgobj, confidence, method, None)
+}
+
+/// 查找所有模板匹配（可取消）
+///
+/// 与 `find_all_template` 相同，但遍历结果矩阵时按行块检查 `cancel`，
+/// 被设置后返回 `ImageMatchError::Cancelled`
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `cancel`: 取消标志
+///
+/// # 返回
+/// 匹配结果列表（按置信度降序）
+pub fn find_all_template_cancellable(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    check_cancelled(Some(cancel))?;
+    let result_mat = run_match(imgsrc, imgobj, rgb, None)?;
+
+    extract_matches_inner(&result_mat, imgobj, confidence, MatchMethod::CcoeffNormed, Some(cancel))
+}
+
+/// 仅执行匹配计算（源图像和模板都由调用方预先准备）
+///
+/// 与 `find_all_template` 行为一致，不包含截图和读取模板的 IO，
+/// 用于单独测量匹配步骤的耗时（见 `benches/match_template.rs`）
+///
+/// # 参数
+/// - `src`: 预先截取的源图像（OpenCV Mat）
+/// - `obj`: 预先读取的模板图像（OpenCV Mat）
+/// - `threshold`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+///
+/// # 返回
+/// 匹配结果列表（按置信度降序）
+pub fn match_only(
+    src: &opencv::core::Mat,
+    obj: &opencv::core::Mat,
+    threshold: f64,
+    rgb: bool,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    find_all_template(src, obj, threshold, rgb)
+}
+
+/// 执行模板匹配，直接返回 `TM_CCOEFF_NORMED` 结果矩阵（置信度图）
+///
+/// `find_all_template` 等函数内部使用的就是这个结果矩阵，从中提取超过阈值的位置。
+/// 需要自定义后处理（自己做 `min_max_loc`、多峰值查找或其他阈值策略）时使用
+///
+/// # 参数
+/// - `src`: 源图像（OpenCV Mat）
+/// - `template`: 模板图像（OpenCV Mat）
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+///
+/// # 返回
+/// 单通道 `CV_32F` 矩阵，尺寸为 (源宽 - 模板宽 + 1) x (源高 - 模板高 + 1)，
+/// (x, y) 处的值为模板左上角放在源图像 (x, y) 时的匹配值，范围 [-1, 1]
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{match_template_raw, read_image};
+///
+/// let src = read_image("screenshot.png")?;
+/// let template = read_image("icon.png")?;
+/// let result = match_template_raw(&src, &template, true)?;
+///
+/// let mut max_val = 0.0;
+/// let mut max_loc = opencv::core::Point::default();
+/// opencv::core::min_max_loc(&result, None, Some(&mut max_val), None, Some(&mut max_loc), &opencv::core::no_array())?;
+/// ```
+pub fn match_template_raw(
+    src: &opencv::core::Mat,
+    template: &opencv::core::Mat,
+    rgb: bool,
+) -> Result<opencv::core::Mat, ImageMatchError> {
+    run_match(src, template, rgb, None)
+}
+
+/// 检查模板是否存在（优化版，只返回布尔值，找到第一个匹配就返回）
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `method`: 匹配方法，决定阈值是下限还是上限
+///
+/// # 返回
+/// 如果找到匹配返回 true，否则返回 false
+fn find_template_exists(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    method: MatchMethod,
+) -> Result<bool, ImageMatchError> {
+    let result_mat = run_match_with_method(imgsrc, imgobj, rgb, None, method)?;
 
-    if rgb {
-        // 彩色模式直接匹配
-        imgproc::match_template(
-            imgsrc,
-            imgobj,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &opencv::core::Mat::default(),
-        )?;
+    // 快速检查：找到第一个超过阈值的匹配就返回
+    // 使用更高效的方式访问数据
+    let rows = result_mat.rows();
+    let cols = result_mat.cols();
+    let threshold_f32 = confidence as f32;
+    let higher_is_better = method.higher_is_better();
+    
+    // 尝试使用连续内存访问（如果 Mat 是连续的）
+    if result_mat.is_continuous() {
+        unsafe {
+            let data_ptr = result_mat.ptr_2d(0, 0)? as *const f32;
+            let total_pixels = (rows * cols) as usize;
+            
+            for i in 0..total_pixels {
+                let confidence_val = *data_ptr.add(i);
+                if (higher_is_better && confidence_val >= threshold_f32)
+                    || (!higher_is_better && confidence_val <= threshold_f32)
+                {
+                    return Ok(true);
+                }
+            }
+        }
     } else {
-        // 灰度模式
-        // 如果源图像已经是灰度图（单通道），直接使用；否则转换
-        let gray_src = if imgsrc.channels() == 1 {
-            imgsrc.clone()
-        } else {
-            let mut gray = opencv::core::Mat::default();
-            imgproc::cvt_color(imgsrc, &mut gray, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
-            gray
-        };
-        
-        // 模板图像转换为灰度
-        let mut gray_obj = opencv::core::Mat::default();
-        imgproc::cvt_color(imgobj, &mut gray_obj, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
-
-        imgproc::match_template(
-            &gray_src,
-            &gray_obj,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &opencv::core::Mat::default(),
-        )?;
+        // 如果不是连续的，按行访问
+        for y in 0..rows {
+            unsafe {
+                let row_ptr = result_mat.ptr_2d(y, 0)? as *const f32;
+                for x in 0..cols {
+                    let confidence_val = *row_ptr.add(x as usize);
+                    if (higher_is_better && confidence_val >= threshold_f32)
+                        || (!higher_is_better && confidence_val <= threshold_f32)
+                    {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
     }
 
-    // 快速检查：找到第一个超过阈值的匹配就返回
-    // 使用更高效的方式访问数据
+    Ok(false)
+}
+
+/// 查找模板并返回第一个匹配的坐标（优化版）
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `offset_x`: 截图区域的 X 偏移（用于计算绝对坐标）
+/// - `offset_y`: 截图区域的 Y 偏移（用于计算绝对坐标）
+///
+/// # 返回
+/// 如果找到匹配，返回绝对坐标 (中心点 x, 中心点 y)，否则返回 (0, 0)
+pub fn find_template_coord(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    offset_x: i32,
+    offset_y: i32,
+) -> Result<(i32, i32), ImageMatchError> {
+    let result_mat = run_match(imgsrc, imgobj, rgb, None)?;
+
+    // 获取模板尺寸，用于计算中心点
+    let template_size = imgobj.size()?;
+    let template_w = template_size.width;
+    let template_h = template_size.height;
+
+    // 快速查找：找到第一个超过阈值的匹配就返回坐标
     let rows = result_mat.rows();
     let cols = result_mat.cols();
     let threshold_f32 = confidence as f32;
@@ -378,7 +1728,17 @@ fn find_template_exists(
             for i in 0..total_pixels {
                 let confidence_val = *data_ptr.add(i);
                 if confidence_val >= threshold_f32 {
-                    return Ok(true);
+                    // 计算在结果矩阵中的位置
+                    let y = (i / cols as usize) as i32;
+                    let x = (i % cols as usize) as i32;
+                    
+                    // 计算中心点坐标（相对于截图区域）
+                    // 使用浮点数计算然后四舍五入（与 extract_matches 保持一致）
+                    let center_x = (x as f64 + template_w as f64 / 2.0).round() as i32;
+                    let center_y = (y as f64 + template_h as f64 / 2.0).round() as i32;
+                    
+                    // 转换为绝对坐标
+                    return Ok((offset_x + center_x, offset_y + center_y));
                 }
             }
         }
@@ -390,127 +1750,574 @@ fn find_template_exists(
                 for x in 0..cols {
                     let confidence_val = *row_ptr.add(x as usize);
                     if confidence_val >= threshold_f32 {
-                        return Ok(true);
+                        // 计算中心点坐标（相对于截图区域）
+                        // 使用浮点数计算然后四舍五入（与 extract_matches 保持一致）
+                        let center_x = (x as f64 + template_w as f64 / 2.0).round() as i32;
+                        let center_y = (y as f64 + template_h as f64 / 2.0).round() as i32;
+                        
+                        // 转换为绝对坐标
+                        return Ok((offset_x + center_x, offset_y + center_y));
                     }
                 }
             }
         }
     }
 
-    Ok(false)
+    // 未找到匹配
+    Ok((0, 0))
+}
+
+/// 查找互不重叠的模板匹配（迭代 minMaxLoc）
+///
+/// 与 `find_all_template` 收集所有超过阈值的像素不同，这里每次取结果矩阵的全局最大值，
+/// 记录后将其周围模板大小的邻域抑制掉，再继续寻找下一个最大值，
+/// 直到最大值低于阈值或已找到 `max_matches` 个。每个目标天然只产生一个结果，无需再做 NMS
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `max_matches`: 最多返回的匹配数量
+///
+/// # 返回
+/// 匹配结果列表（按置信度降序）
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{read_image, find_distinct_template};
+///
+/// let src = read_image("screenshot.png")?;
+/// let template = read_image("icon.png")?;
+/// let results = find_distinct_template(&src, &template, 0.8, true, 10)?;
+/// println!("找到 {} 个图标", results.len());
+/// ```
+pub fn find_distinct_template(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    max_matches: usize,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    let mut matches = Vec::new();
+    if max_matches == 0 {
+        return Ok(matches);
+    }
+
+    let mut result_mat = run_match(imgsrc, imgobj, rgb, None)?;
+
+    let template_size = imgobj.size()?;
+    let template_w = template_size.width;
+    let template_h = template_size.height;
+
+    let rows = result_mat.rows();
+    let cols = result_mat.cols();
+
+    while matches.len() < max_matches {
+        let mut max_val = 0.0;
+        let mut max_loc = opencv::core::Point::default();
+        opencv::core::min_max_loc(
+            &result_mat,
+            None,
+            Some(&mut max_val),
+            None,
+            Some(&mut max_loc),
+            &opencv::core::Mat::default(),
+        )?;
+
+        if max_val < confidence {
+            break;
+        }
+
+        matches.push(build_match_result(max_loc.x, max_loc.y, template_w, template_h, max_val));
+
+        // 抑制以最大值为中心、模板大小的邻域，避免同一目标被重复找到
+        let y_start = (max_loc.y - template_h / 2).max(0);
+        let y_end = (max_loc.y + template_h / 2 + 1).min(rows);
+        let x_start = (max_loc.x - template_w / 2).max(0);
+        let x_end = (max_loc.x + template_w / 2 + 1).min(cols);
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                *result_mat.at_2d_mut::<f32>(y, x)? = f32::MIN;
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+/// 查找置信度最高的前 N 个不重复匹配
+///
+/// `find_all_template` 会返回所有超过阈值的像素位置，阈值较低、屏幕较大时可能有数万个几乎相同的结果。
+/// 这里只把局部极大值放入容量有限的最小堆，不会构造完整的结果列表，
+/// 再做非极大值抑制（`DEFAULT_NMS_IOU_THRESHOLD`）并截取前 `max_results` 个。
+/// 噪声较大时同一目标附近会有多个局部极大值，抑制后不足 `max_results` 个且有候选被丢弃时，
+/// 加倍堆容量重新筛选，因此结果与对全部局部极大值做非极大值抑制后取前 N 个一致
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `confidence`: 相似度阈值 (0.0-1.0)
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+/// - `max_results`: 最多返回的匹配数量，为 0 时返回空列表
+///
+/// # 返回
+/// 匹配结果列表（按置信度降序，最多 `max_results` 个）
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{read_image, find_top_matches};
+///
+/// let src = read_image("screenshot.png")?;
+/// let template = read_image("item.png")?;
+/// let top = find_top_matches(&src, &template, 0.7, true, 5)?;
+/// ```
+pub fn find_top_matches(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    confidence: f64,
+    rgb: bool,
+    max_results: usize,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    if max_results == 0 {
+        return Ok(Vec::new());
+    }
+
+    let result_mat = run_match(imgsrc, imgobj, rgb, None)?;
+    let template_size = imgobj.size()?;
+
+    // 每行只取一次切片，后续扫描和邻域比较都直接读切片
+    let rows = (0..result_mat.rows())
+        .map(|y| result_mat.at_row::<f32>(y))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 非极大值抑制可能去掉部分候选，多保留一些局部极大值
+    let mut capacity = max_results.saturating_mul(TOP_MATCH_CANDIDATE_FACTOR);
+    loop {
+        let (heap, dropped) = collect_top_candidates(&rows, confidence, capacity);
+
+        let candidates = heap
+            .into_iter()
+            .map(|Reverse(candidate)| {
+                build_match_result(
+                    candidate.x,
+                    candidate.y,
+                    template_size.width,
+                    template_size.height,
+                    candidate.confidence as f64,
+                )
+            })
+            .collect();
+
+        let mut matches = non_max_suppression(candidates, DEFAULT_NMS_IOU_THRESHOLD);
+        if matches.len() >= max_results || !dropped {
+            matches.truncate(max_results);
+            return Ok(matches);
+        }
+
+        capacity = capacity.saturating_mul(2);
+    }
+}
+
+/// `find_top_matches` 初始保留的候选数量是 `max_results` 的多少倍
+const TOP_MATCH_CANDIDATE_FACTOR: usize = 4;
+
+/// `find_top_matches` 堆中的候选位置，按置信度排序
+#[derive(Debug, Clone, Copy)]
+struct TopCandidate {
+    confidence: f32,
+    x: i32,
+    y: i32,
+}
+
+impl PartialEq for TopCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for TopCandidate {}
+
+impl PartialOrd for TopCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.confidence.total_cmp(&other.confidence)
+    }
+}
+
+/// 从结果矩阵的各行中选出置信度最高的 `capacity` 个局部极大值
+///
+/// 返回 (最小堆, 是否有超过阈值的局部极大值因容量不足被丢弃)
+fn collect_top_candidates(
+    rows: &[&[f32]],
+    confidence: f64,
+    capacity: usize,
+) -> (BinaryHeap<Reverse<TopCandidate>>, bool) {
+    let mut heap: BinaryHeap<Reverse<TopCandidate>> = BinaryHeap::with_capacity(capacity + 1);
+    let mut dropped = false;
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, &value) in row.iter().enumerate() {
+            if !value.is_finite() || (value as f64) < confidence {
+                continue;
+            }
+            let heap_full = heap.len() == capacity;
+            if heap_full && heap.peek().is_some_and(|min| value <= min.0.confidence) {
+                dropped |= is_local_maximum(rows, x, y, value);
+                continue;
+            }
+            if !is_local_maximum(rows, x, y, value) {
+                continue;
+            }
+
+            heap.push(Reverse(TopCandidate { confidence: value, x: x as i32, y: y as i32 }));
+            if heap.len() > capacity {
+                heap.pop();
+                dropped = true;
+            }
+        }
+    }
+
+    (heap, dropped)
+}
+
+/// (x, y) 处的值是否不小于其 8 邻域内的所有值
+fn is_local_maximum(rows: &[&[f32]], x: usize, y: usize, value: f32) -> bool {
+    let y_range = y.saturating_sub(1)..=(y + 1).min(rows.len() - 1);
+    rows[y_range].iter().all(|row| {
+        let x_range = x.saturating_sub(1)..=(x + 1).min(row.len() - 1);
+        row[x_range].iter().all(|&neighbor| !neighbor.is_finite() || neighbor <= value)
+    })
+}
+
+/// 计算模板在指定位置的匹配置信度（不做区域搜索）
+///
+/// 以 `top_left` 为左上角，从源图像中裁剪出与模板同尺寸的区域，
+/// 只计算这一个位置的 `TM_CCOEFF_NORMED` 得分
+///
+/// # 参数
+/// - `imgsrc`: 源图像（OpenCV Mat）
+/// - `imgobj`: 模板图像（OpenCV Mat）
+/// - `top_left`: 期望位置的左上角坐标 (x, y)，相对于源图像
+/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
+///
+/// # 返回
+/// 该位置的相似度；裁剪区域超出源图像时返回 OpenCV 错误
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{read_image, confidence_at};
+///
+/// let src = read_image("screenshot.png")?;
+/// let template = read_image("button.png")?;
+/// let confidence = confidence_at(&src, &template, (120, 80), true)?;
+/// assert!(confidence >= 0.9);
+/// ```
+pub fn confidence_at(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    top_left: (i32, i32),
+    rgb: bool,
+) -> Result<f64, ImageMatchError> {
+    let template_size = imgobj.size()?;
+    let rect = opencv::core::Rect::new(
+        top_left.0,
+        top_left.1,
+        template_size.width,
+        template_size.height,
+    );
+    let roi = opencv::core::Mat::roi(imgsrc, rect)?.clone_pointee();
+
+    // 裁剪区域与模板同尺寸，结果矩阵为 1x1
+    let result_mat = run_match(&roi, imgobj, rgb, None)?;
+    let confidence_val = *result_mat.at_2d::<f32>(0, 0)?;
+
+    Ok(confidence_val as f64)
+}
+
+/// 计算一组匹配结果中心点的平均位置（质心）
+///
+/// # 参数
+/// - `matches`: 匹配结果列表
+///
+/// # 返回
+/// 所有匹配中心点的平均坐标 (x, y)，列表为空时返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{find_all_template, matches_centroid};
+///
+/// let results = find_all_template(&src, &template, 0.8, true)?;
+/// if let Some((cx, cy)) = matches_centroid(&results) {
+///     println!("图标群中心: ({}, {})", cx, cy);
+/// }
+/// ```
+pub fn matches_centroid(matches: &[MatchResult<i32>]) -> Option<(f64, f64)> {
+    if matches.is_empty() {
+        return None;
+    }
+
+    let (sum_x, sum_y) = matches.iter().fold((0.0, 0.0), |(sx, sy), m| {
+        (sx + *m.result.x(), sy + *m.result.y())
+    });
+    let count = matches.len() as f64;
+
+    Some((sum_x / count, sum_y / count))
+}
+
+/// 计算包围一组匹配结果的最小矩形
+///
+/// # 参数
+/// - `matches`: 匹配结果列表
+///
+/// # 返回
+/// 包围所有匹配矩形的外接矩形 (左上 x, 左上 y, 右下 x, 右下 y)，列表为空时返回 None
+pub fn matches_bounding_box(matches: &[MatchResult<i32>]) -> Option<(i32, i32, i32, i32)> {
+    matches
+        .iter()
+        .flat_map(|m| m.rectangle.iter())
+        .fold(None, |bbox, point| {
+            let (x, y) = (*point.x(), *point.y());
+            Some(match bbox {
+                None => (x, y, x, y),
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+            })
+        })
+}
+
+/// 在图像上绘制匹配结果的矩形框，用于生成调试图片
+///
+/// 除矩形框外，还会在每个匹配的中心点 (`result`) 绘制十字线，便于确认点击位置
+///
+/// # 参数
+/// - `img`: 要绘制的图像（BGR 格式），匹配结果的坐标需相对于该图像
+/// - `matches`: 匹配结果列表
+/// - `color`: 矩形框颜色
+/// - `thickness`: 线宽（像素），负数表示填充
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::{read_image, find_all_template, draw_matches};
+/// use image_utils::types::RgbColor;
+///
+/// let mut src = read_image("screenshot.png")?;
+/// let template = read_image("icon.png")?;
+/// let results = find_all_template(&src, &template, 0.8, true)?;
+/// draw_matches(&mut src, &results, RgbColor::new(255, 0, 0), 2)?;
+/// opencv::imgcodecs::imwrite("debug.png", &src, &opencv::core::Vector::new())?;
+/// ```
+pub fn draw_matches(
+    img: &mut opencv::core::Mat,
+    matches: &[MatchResult<i32>],
+    color: RgbColor,
+    thickness: i32,
+) -> Result<(), ImageMatchError> {
+    // OpenCV 使用 BGR 顺序
+    let scalar = opencv::core::Scalar::new(color.b as f64, color.g as f64, color.r as f64, 0.0);
+
+    // 十字线不能填充，线宽至少为 1
+    let line_thickness = thickness.max(1);
+
+    for match_result in matches {
+        let rect = match_result.to_cv_rect();
+        imgproc::rectangle(img, rect, scalar, thickness, imgproc::LINE_8, 0)?;
+
+        // 十字线臂长取矩形短边的 1/4，至少 2 像素
+        let arm = (rect.width.min(rect.height) / 4).max(2);
+        let center = match_result.result.round_to_i32();
+        let (cx, cy) = (*center.x(), *center.y());
+        imgproc::line(
+            img,
+            opencv::core::Point::new(cx - arm, cy),
+            opencv::core::Point::new(cx + arm, cy),
+            scalar,
+            line_thickness,
+            imgproc::LINE_8,
+            0,
+        )?;
+        imgproc::line(
+            img,
+            opencv::core::Point::new(cx, cy - arm),
+            opencv::core::Point::new(cx, cy + arm),
+            scalar,
+            line_thickness,
+            imgproc::LINE_8,
+            0,
+        )?;
+    }
+
+    Ok(())
 }
 
-/// 查找模板并返回第一个匹配的坐标（优化版）
+/// 截图找图并把匹配结果画在截图上保存，用于调整阈值时直观查看匹配位置
+///
+/// 相当于依次调用 `screenshot_to_mat`、`find_all_template`、`draw_matches` 和 `imwrite`，
+/// 匹配结果按 `draw_matches` 的方式用矩形框和十字线标出
 ///
 /// # 参数
-/// - `imgsrc`: 源图像（OpenCV Mat）
-/// - `imgobj`: 模板图像（OpenCV Mat）
-/// - `confidence`: 相似度阈值 (0.0-1.0)
-/// - `rgb`: 是否使用彩色匹配（true=彩色，false=灰度）
-/// - `offset_x`: 截图区域的 X 偏移（用于计算绝对坐标）
-/// - `offset_y`: 截图区域的 Y 偏移（用于计算绝对坐标）
+/// - `x`: 截图区域左上角 X
+/// - `y`: 截图区域左上角 Y
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `image_path`: 模板图片路径
+/// - `confidence`: 相似度阈值
+/// - `rgb`: 是否使用彩色匹配
+/// - `save_path`: 调试图片保存路径
+/// - `color`: 标记颜色（RGB）
+/// - `thickness`: 矩形框线宽，负数表示填充
 ///
 /// # 返回
-/// 如果找到匹配，返回绝对坐标 (中心点 x, 中心点 y)，否则返回 (0, 0)
-pub fn find_template_coord(
+/// 所有匹配结果（绝对坐标）
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_and_save_debug;
+/// use image_utils::types::RgbColor;
+///
+/// let matches = find_and_save_debug(
+///     0, 0, 1920, 1080, "./icon.png", 0.8, false, "./debug.png", RgbColor::new(255, 0, 0), 2,
+/// )?;
+/// println!("找到 {} 个匹配，见 debug.png", matches.len());
+/// ```
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
+pub fn find_and_save_debug(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    image_path: &str,
+    confidence: f64,
+    rgb: bool,
+    save_path: &str,
+    color: RgbColor,
+    thickness: i32,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    let mut src = screenshot_to_mat(x as u32, y as u32, width, height)?;
+    let template = read_image(image_path)?;
+
+    let matches = find_all_template(&src, &template, confidence, rgb)?;
+    draw_matches(&mut src, &matches, color, thickness)?;
+
+    if !imgcodecs::imwrite(save_path, &src, &opencv::core::Vector::new())? {
+        return Err(ImageMatchError::OpenCV(opencv::Error::new(
+            opencv::core::StsError,
+            format!("保存调试图片失败: {}", save_path),
+        )));
+    }
+
+    Ok(matches
+        .iter()
+        .map(|match_result| offset_match_result(match_result, x, y))
+        .collect())
+}
+
+/// 执行模板匹配，返回 `TM_CCOEFF_NORMED` 结果矩阵
+///
+/// 灰度模式下，源图像和模板已经是单通道时直接使用，否则转换为灰度。
+/// `blur` 为 Some 时，匹配前对源图像和模板做同样的高斯模糊
+pub(crate) fn run_match(
     imgsrc: &opencv::core::Mat,
     imgobj: &opencv::core::Mat,
-    confidence: f64,
     rgb: bool,
-    offset_x: i32,
-    offset_y: i32,
-) -> Result<(i32, i32), ImageMatchError> {
+    blur: Option<i32>,
+) -> Result<opencv::core::Mat, ImageMatchError> {
+    run_match_with_method(imgsrc, imgobj, rgb, blur, MatchMethod::CcoeffNormed)
+}
+
+/// 执行模板匹配，使用指定的匹配方法，其余与 `run_match` 相同
+fn run_match_with_method(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+    rgb: bool,
+    blur: Option<i32>,
+    method: MatchMethod,
+) -> Result<opencv::core::Mat, ImageMatchError> {
+    check_template_fits(imgsrc, imgobj)?;
+
     let mut result_mat = opencv::core::Mat::default();
 
-    if rgb {
+    let gray_src;
+    let gray_obj;
+    let (src, obj) = if rgb {
         // 彩色模式直接匹配
-        imgproc::match_template(
-            imgsrc,
-            imgobj,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &opencv::core::Mat::default(),
-        )?;
+        (imgsrc, imgobj)
     } else {
         // 灰度模式
-        // 如果源图像已经是灰度图（单通道），直接使用；否则转换
-        let gray_src = if imgsrc.channels() == 1 {
-            imgsrc.clone()
+        // 如果源图像已经是灰度图（单通道），直接使用，避免拷贝
+        let src = if imgsrc.channels() == 1 {
+            imgsrc
         } else {
-            let mut gray = opencv::core::Mat::default();
-            imgproc::cvt_color(imgsrc, &mut gray, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
-            gray
+            gray_src = to_gray_mat(imgsrc)?;
+            &gray_src
         };
-        
-        // 模板图像转换为灰度
-        let mut gray_obj = opencv::core::Mat::default();
-        imgproc::cvt_color(imgobj, &mut gray_obj, imgproc::COLOR_BGR2GRAY, 0, DEFAULT_ALGORITHM_HINT)?;
-
-        imgproc::match_template(
-            &gray_src,
-            &gray_obj,
-            &mut result_mat,
-            imgproc::TM_CCOEFF_NORMED,
-            &opencv::core::Mat::default(),
-        )?;
-    }
 
-    // 获取模板尺寸，用于计算中心点
-    let template_size = imgobj.size()?;
-    let template_w = template_size.width;
-    let template_h = template_size.height;
+        // 模板图像转换为灰度（预先转换好的灰度模板直接使用）
+        let obj = if imgobj.channels() == 1 {
+            imgobj
+        } else {
+            gray_obj = to_gray_mat(imgobj)?;
+            &gray_obj
+        };
+        (src, obj)
+    };
 
-    // 快速查找：找到第一个超过阈值的匹配就返回坐标
-    let rows = result_mat.rows();
-    let cols = result_mat.cols();
-    let threshold_f32 = confidence as f32;
-    
-    // 尝试使用连续内存访问（如果 Mat 是连续的）
-    if result_mat.is_continuous() {
-        unsafe {
-            let data_ptr = result_mat.ptr_2d(0, 0)? as *const f32;
-            let total_pixels = (rows * cols) as usize;
-            
-            for i in 0..total_pixels {
-                let confidence_val = *data_ptr.add(i);
-                if confidence_val >= threshold_f32 {
-                    // 计算在结果矩阵中的位置
-                    let y = (i / cols as usize) as i32;
-                    let x = (i % cols as usize) as i32;
-                    
-                    // 计算中心点坐标（相对于截图区域）
-                    // 使用浮点数计算然后四舍五入（与 extract_matches 保持一致）
-                    let center_x = (x as f64 + template_w as f64 / 2.0).round() as i32;
-                    let center_y = (y as f64 + template_h as f64 / 2.0).round() as i32;
-                    
-                    // 转换为绝对坐标
-                    return Ok((offset_x + center_x, offset_y + center_y));
-                }
-            }
-        }
-    } else {
-        // 如果不是连续的，按行访问
-        for y in 0..rows {
-            unsafe {
-                let row_ptr = result_mat.ptr_2d(y, 0)? as *const f32;
-                for x in 0..cols {
-                    let confidence_val = *row_ptr.add(x as usize);
-                    if confidence_val >= threshold_f32 {
-                        // 计算中心点坐标（相对于截图区域）
-                        // 使用浮点数计算然后四舍五入（与 extract_matches 保持一致）
-                        let center_x = (x as f64 + template_w as f64 / 2.0).round() as i32;
-                        let center_y = (y as f64 + template_h as f64 / 2.0).round() as i32;
-                        
-                        // 转换为绝对坐标
-                        return Ok((offset_x + center_x, offset_y + center_y));
-                    }
-                }
-            }
+    let mut blurred_src = opencv::core::Mat::default();
+    let mut blurred_obj = opencv::core::Mat::default();
+    let (src, obj) = match blur_kernel_size(blur) {
+        Some(ksize) => {
+            let size = opencv::core::Size::new(ksize, ksize);
+            imgproc::gaussian_blur_def(src, &mut blurred_src, size, 0.0)?;
+            imgproc::gaussian_blur_def(obj, &mut blurred_obj, size, 0.0)?;
+            (&blurred_src, &blurred_obj)
         }
+        None => (src, obj),
+    };
+
+    imgproc::match_template(
+        src,
+        obj,
+        &mut result_mat,
+        method.to_cv(),
+        &opencv::core::Mat::default(),
+    )?;
+
+    Ok(result_mat)
+}
+
+/// 模板的宽或高大于源图像时返回 `TemplateTooLarge`
+///
+/// OpenCV 的 `match_template` 在这种情况下只会报一个断言失败，通常是截图区域传错了
+fn check_template_fits(
+    imgsrc: &opencv::core::Mat,
+    imgobj: &opencv::core::Mat,
+) -> Result<(), ImageMatchError> {
+    let src_size = imgsrc.size()?;
+    let obj_size = imgobj.size()?;
+    if obj_size.width > src_size.width || obj_size.height > src_size.height {
+        return Err(ImageMatchError::TemplateTooLarge {
+            template: (obj_size.width, obj_size.height),
+            src: (src_size.width, src_size.height),
+        });
     }
+    Ok(())
+}
 
-    // 未找到匹配
-    Ok((0, 0))
+/// 将模糊参数规整为有效的高斯核尺寸
+///
+/// 高斯核尺寸必须为正奇数：小于等于 1 视为不模糊，偶数向上取为奇数
+fn blur_kernel_size(blur: Option<i32>) -> Option<i32> {
+    match blur {
+        Some(ksize) if ksize > 1 => Some(ksize | 1),
+        _ => None,
+    }
 }
 
 /// 确保图像是3通道的CV_8U类型
@@ -540,10 +2347,27 @@ pub fn find_template_coord(
 // }
 
 /// 从匹配结果矩阵中提取所有匹配点
-fn extract_matches(
+pub(crate) fn extract_matches(
+    match_result: &opencv::core::Mat,
+    template: &opencv::core::Mat,
+    threshold: f64,
+) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
+    extract_matches_inner(match_result, template, threshold, MatchMethod::CcoeffNormed, None)
+}
+
+/// 每遍历多少行检查一次取消标志
+const CANCEL_CHECK_ROWS: i32 = 64;
+
+/// 从匹配结果矩阵中提取所有匹配点，`cancel` 为 Some 时每 `CANCEL_CHECK_ROWS` 行检查一次
+///
+/// 按 `method` 的方向比较阈值，结果按相似程度从高到低排序
+/// （`SqdiffNormed` 时即按匹配值升序）
+fn extract_matches_inner(
     match_result: &opencv::core::Mat,
     template: &opencv::core::Mat,
     threshold: f64,
+    method: MatchMethod,
+    cancel: Option<&AtomicBool>,
 ) -> Result<Vec<MatchResult<i32>>, ImageMatchError> {
     let mut matches = Vec::new();
 
@@ -556,42 +2380,102 @@ fn extract_matches(
 
     // 遍历所有像素，找到超过阈值的匹配
     for y in 0..rows {
+        if y % CANCEL_CHECK_ROWS == 0 {
+            check_cancelled(cancel)?;
+        }
+
         for x in 0..cols {
             unsafe {
                 let confidence_val = *match_result.at_2d_unchecked::<f32>(y, x)?;
 
-                if confidence_val as f64 >= threshold {
-                    // 计算中心点
-                    let center_x = x as f64 + template_w as f64 / 2.0;
-                    let center_y = y as f64 + template_h as f64 / 2.0;
-
-                    // 计算四个角点
-                    let rectangle = [
-                        Point::new(x, y),                                    // 左上
-                        Point::new(x, y + template_h),                       // 左下
-                        Point::new(x + template_w, y),                       // 右上
-                        Point::new(x + template_w, y + template_h),         // 右下
-                    ];
-
-                    matches.push(MatchResult {
-                        confidence: confidence_val as f64,
-                        rectangle,
-                        result: Point::new(center_x, center_y),
-                    });
+                // 带掩码匹配时分母可能为 0，产生 NaN/Inf
+                if confidence_val.is_finite() && method.passes(confidence_val as f64, threshold) {
+                    matches.push(build_match_result(x, y, template_w, template_h, confidence_val as f64));
                 }
             }
         }
     }
 
-    // 按置信度降序排序
+    // 按置信度降序排序（平方差方法按升序）
     matches.sort_by(|a, b| {
-        b.confidence.partial_cmp(&a.confidence)
-            .unwrap_or(std::cmp::Ordering::Equal)
+        let ordering = b.confidence.partial_cmp(&a.confidence)
+            .unwrap_or(std::cmp::Ordering::Equal);
+        if method.higher_is_better() {
+            ordering
+        } else {
+            ordering.reverse()
+        }
     });
 
     Ok(matches)
 }
 
+/// 取消标志被设置时返回 `ImageMatchError::Cancelled`
+fn check_cancelled(cancel: Option<&AtomicBool>) -> Result<(), ImageMatchError> {
+    match cancel {
+        Some(flag) if flag.load(Ordering::Relaxed) => Err(ImageMatchError::Cancelled),
+        _ => Ok(()),
+    }
+}
+
+/// 按比例缩放图像（缩小时使用 INTER_AREA，放大时使用 INTER_LINEAR）
+fn resize_mat(mat: &opencv::core::Mat, scale: f64) -> Result<opencv::core::Mat, ImageMatchError> {
+    let interpolation = if scale < 1.0 {
+        imgproc::INTER_AREA
+    } else {
+        imgproc::INTER_LINEAR
+    };
+
+    let mut resized = opencv::core::Mat::default();
+    imgproc::resize(
+        mat,
+        &mut resized,
+        opencv::core::Size::default(),
+        scale,
+        scale,
+        interpolation,
+    )?;
+    Ok(resized)
+}
+
+/// 根据匹配位置（左上角）和模板尺寸构造匹配结果
+fn build_match_result(
+    x: i32,
+    y: i32,
+    template_w: i32,
+    template_h: i32,
+    confidence: f64,
+) -> MatchResult<i32> {
+    // 计算中心点
+    let center_x = x as f64 + template_w as f64 / 2.0;
+    let center_y = y as f64 + template_h as f64 / 2.0;
+
+    // 计算四个角点
+    let rectangle = [
+        Point::new(x, y),                                    // 左上
+        Point::new(x, y + template_h),                       // 左下
+        Point::new(x + template_w, y),                       // 右上
+        Point::new(x + template_w, y + template_h),         // 右下
+    ];
+
+    MatchResult {
+        confidence,
+        rectangle,
+        result: Point::new(center_x, center_y),
+    }
+}
+
+/// 将匹配结果平移 (dx, dy)，用于把相对截图的坐标转换为屏幕绝对坐标
+fn offset_match_result(result: &MatchResult<i32>, dx: i32, dy: i32) -> MatchResult<i32> {
+    MatchResult {
+        confidence: result.confidence,
+        rectangle: result
+            .rectangle
+            .map(|point| Point::new(*point.x() + dx, *point.y() + dy)),
+        result: Point::new(*result.result.x() + dx as f64, *result.result.y() + dy as f64),
+    }
+}
+
 /// 找字_图库图片找字_find_all_template_线程版（Rust 实现）
 /// 
 /// 在指定区域中查找数字 0-9，使用并行处理提高性能
@@ -629,56 +2513,215 @@ pub fn find_characters_from_library_threaded(
     library_path: &str,
     threshold: f64,
 ) -> Result<String, ImageMatchError> {
+    let library = digit_library(library_path);
+    let charset = charset_refs(&library);
+    find_characters_inner(x1, y1, width, height, &charset, threshold, None).map(characters_to_string)
+}
+
+/// 找字_图库图片找字_find_all_template_线程版（可取消）
+///
+/// 与 `find_characters_from_library_threaded` 相同，但各个 rayon 工作线程在读取模板前、
+/// 遍历结果矩阵时都会检查 `cancel`，被设置后返回 `ImageMatchError::Cancelled`
+pub fn find_characters_from_library_threaded_cancellable(
+    x1: i32,
+    y1: i32,
+    width: u32,
+    height: u32,
+    library_path: &str,
+    threshold: f64,
+    cancel: &AtomicBool,
+) -> Result<String, ImageMatchError> {
+    let library = digit_library(library_path);
+    let charset = charset_refs(&library);
+    find_characters_inner(x1, y1, width, height, &charset, threshold, Some(cancel)).map(characters_to_string)
+}
+
+/// 图库找字（任意字符集，线程版）
+///
+/// 与 `find_characters_from_library_threaded` 相同，但字符与模板的对应关系由调用方提供，
+/// 可以识别字母、符号等任意字符，而不仅是数字
+///
+/// # 参数
+/// - `x1`: 截图区域左上角 X 坐标
+/// - `y1`: 截图区域左上角 Y 坐标
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `charset`: (字符, 模板图片路径) 列表，读取失败的模板会被跳过
+/// - `threshold`: 相似度阈值（默认 0.9）
+///
+/// # 返回
+/// 识别到的字符串（按从左到右的顺序）
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_characters_from_charset_threaded;
+///
+/// let charset = [('A', "lib/A.bmp"), ('B', "lib/B.bmp"), ('+', "lib/plus.bmp")];
+/// let text = find_characters_from_charset_threaded(100, 100, 300, 40, &charset, 0.9)?;
+/// ```
+pub fn find_characters_from_charset_threaded(
+    x1: i32,
+    y1: i32,
+    width: u32,
+    height: u32,
+    charset: &[(char, &str)],
+    threshold: f64,
+) -> Result<String, ImageMatchError> {
+    find_characters_inner(x1, y1, width, height, charset, threshold, None).map(characters_to_string)
+}
+
+/// 图库找字（带位置，线程版）- 返回每个识别到的字符及其中心点坐标
+///
+/// 与 `find_characters_from_library_threaded` 使用相同的匹配、去重和排序逻辑，
+/// 但不拼接为字符串，而是返回每个字符的位置，可用于点击显示数字中的某一位
+///
+/// # 参数
+/// - `x1`: 截图区域左上角 X 坐标
+/// - `y1`: 截图区域左上角 Y 坐标
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `library_path`: 图库路径（包含 0.bmp 到 9.bmp 的文件夹）
+/// - `threshold`: 相似度阈值（默认 0.9）
+///
+/// # 返回
+/// (字符, 中心点 x, 中心点 y) 列表，坐标为屏幕绝对坐标，按从左到右的顺序
+///
+/// # 示例
+/// ```rust
+/// use image_utils::image_match::find_characters_with_positions;
+///
+/// let characters = find_characters_with_positions(100, 100, 800, 600, "C:\\path\\to\\library", 0.9)?;
+/// if let Some((ch, x, y)) = characters.last() {
+///     println!("最后一位 {} 在 ({}, {})", ch, x, y);
+/// }
+/// ```
+pub fn find_characters_with_positions(
+    x1: i32,
+    y1: i32,
+    width: u32,
+    height: u32,
+    library_path: &str,
+    threshold: f64,
+) -> Result<Vec<(char, i32, i32)>, ImageMatchError> {
+    let library = digit_library(library_path);
+    let charset = charset_refs(&library);
+    let characters = find_characters_inner(x1, y1, width, height, &charset, threshold, None)?;
+
+    Ok(characters
+        .into_iter()
+        .map(|(ch, match_result)| {
+            let center = match_result.result.round_to_i32();
+            (ch, x1 + center.x(), y1 + center.y())
+        })
+        .collect())
+}
+
+/// 构建数字图库的字符集：0-9 对应 library_path 下的 0.bmp 到 9.bmp
+fn digit_library(library_path: &str) -> Vec<(char, String)> {
+    (0..10u8)
+        .filter_map(|digit| {
+            let template_path = Path::new(library_path).join(format!("{}.bmp", digit));
+            template_path
+                .to_str()
+                .map(|path| (char::from(b'0' + digit), path.to_string()))
+        })
+        .collect()
+}
+
+/// 将拥有所有权的字符集转换为借用形式
+fn charset_refs(library: &[(char, String)]) -> Vec<(char, &str)> {
+    library.iter().map(|(ch, path)| (*ch, path.as_str())).collect()
+}
+
+/// 将识别结果按顺序拼接为字符串
+fn characters_to_string(characters: Vec<(char, MatchResult<i32>)>) -> String {
+    characters.into_iter().map(|(ch, _)| ch).collect()
+}
+
+/// 图库找字的公共实现，返回按 X 坐标从左到右排序的 (字符, 匹配结果)，坐标相对于截图区域
+fn find_characters_inner(
+    x1: i32,
+    y1: i32,
+    width: u32,
+    height: u32,
+    charset: &[(char, &str)],
+    threshold: f64,
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<(char, MatchResult<i32>)>, ImageMatchError> {
     // 截图（使用灰度模式，与 Python 版本保持一致）
     let screenshot = screenshot_to_mat_gray(x1 as u32, y1 as u32, width, height)?;
     let screenshot_arc = Arc::new(screenshot);
-    let library_path = Arc::new(library_path.to_string());
-
-    // 使用并行处理查找所有数字（0-9）
-    let results: Vec<(f64, u8)> = (0..10)
-        .into_par_iter()
-        .flat_map(|digit| {
-            // 构建模板图片路径：library_path + "\\" + digit + ".bmp"
-            let template_path = Path::new(library_path.as_str())
-                .join(format!("{}.bmp", digit));
-            
-            let template_path_str = match template_path.to_str() {
-                Some(s) => s,
-                None => return Vec::new(),
-            };
-            
+
+    // 使用并行处理查找字符集中的所有字符
+    let results: Vec<(char, MatchResult<i32>)> = charset
+        .par_iter()
+        .flat_map(|&(ch, template_path)| {
+            if check_cancelled(cancel).is_err() {
+                return Vec::new();
+            }
+
             // 读取模板图片
-            let template = match read_image(template_path_str) {
+            let template = match read_image(template_path) {
                 Ok(t) => t,
                 Err(_) => return Vec::new(), // 如果文件不存在，跳过
             };
 
             // 在截图中查找所有匹配
-            let matches = match find_all_template(&screenshot_arc, &template, threshold, false) {
+            let matches = match run_match(&screenshot_arc, &template, false, None)
+                .and_then(|result_mat| {
+                    extract_matches_inner(&result_mat, &template, threshold, MatchMethod::CcoeffNormed, cancel)
+                })
+            {
                 Ok(m) => m,
                 Err(_) => return Vec::new(),
             };
 
-            // 收集所有匹配结果：[(x坐标, 数字)]
+            // 收集所有匹配结果：[(字符, 匹配结果)]
             matches
                 .into_iter()
-                .map(|match_result| {
-                    let x = *match_result.result.x();
-                    (x, digit as u8)
-                })
+                .map(|match_result| (ch, match_result))
                 .collect::<Vec<_>>()
         })
         .collect();
 
-    // 按 X 坐标排序
-    let mut sorted_results = results;
-    sorted_results.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    // 工作线程被取消时返回的是不完整的结果
+    check_cancelled(cancel)?;
 
-    // 生成字符串
-    let content_string: String = sorted_results
-        .iter()
-        .map(|(_, digit)| char::from(b'0' + *digit))
-        .collect();
+    // 去掉同一位置的重复匹配后按 X 坐标排序
+    let mut sorted_results = suppress_overlapping_characters(results);
+    sorted_results.sort_by(|a, b| {
+        a.1.result.x().partial_cmp(b.1.result.x()).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(sorted_results)
+}
 
-    Ok(content_string)
-}
\ No newline at end of file
+/// 对识别到的字符做非极大值抑制
+///
+/// 同一个字符位置可能在相邻几个像素都超过阈值（例如 "8" 被识别成 "888"），
+/// 按置信度从高到低保留，丢弃与已保留匹配在 X、Y 方向的距离都小于其模板宽、高的匹配，
+/// 间距正常的相邻字符不受影响
+pub(crate) fn suppress_overlapping_characters(
+    mut results: Vec<(char, MatchResult<i32>)>,
+) -> Vec<(char, MatchResult<i32>)> {
+    results.sort_by(|a, b| {
+        b.1.confidence.partial_cmp(&a.1.confidence).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut kept: Vec<(char, MatchResult<i32>)> = Vec::new();
+    for (ch, match_result) in results {
+        let is_overlapping = kept.iter().any(|(_, existing)| {
+            let template_w = existing.width() as f64;
+            let template_h = existing.height() as f64;
+            let dx = (match_result.result.x() - existing.result.x()).abs();
+            let dy = (match_result.result.y() - existing.result.y()).abs();
+            dx < template_w && dy < template_h
+        });
+
+        if !is_overlapping {
+            kept.push((ch, match_result));
+        }
+    }
+
+    kept
+}
@@ -1,6 +1,7 @@
 use opencv::Error;
 use thiserror::Error;
 use crate::screenshot_error::ScreenshotError;
+use crate::types::MatchMethod;
 
 #[derive(Error, Debug)]
 pub enum ImageMatchError {
@@ -10,4 +11,19 @@ pub enum ImageMatchError {
     OpenCV(#[from]Error),
     #[error("无法读取图像: {0}")]
     CanNotReadImage(String),
+    #[error("无法解码图像数据（{0} 字节）")]
+    CanNotDecodeImage(usize),
+    #[error("相似度百分比超出范围 [0, 100]: {0}")]
+    InvalidThresholdPercent(f64),
+    #[error("匹配方法 {0:?} 不支持掩码，请使用 CcorrNormed 或 SqdiffNormed")]
+    MaskUnsupported(MatchMethod),
+    #[error("模板尺寸 {template:?} 大于源图像尺寸 {src:?}，请检查截图区域")]
+    TemplateTooLarge {
+        /// 模板的 (宽, 高)
+        template: (i32, i32),
+        /// 源图像的 (宽, 高)
+        src: (i32, i32),
+    },
+    #[error("操作已取消")]
+    Cancelled,
 }
\ No newline at end of file
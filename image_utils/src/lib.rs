@@ -7,4 +7,6 @@ pub mod image_match_error;
 pub mod screenshot_error;
 pub mod consts;
 pub mod utils;
-
+pub mod monitor_context;
+pub mod ocr_engine;
+pub mod screen_frame;
@@ -0,0 +1,116 @@
+//! 多显示器坐标上下文
+
+use xcap::Monitor;
+use crate::color_detection::find_color_in_mat_coord;
+use crate::image_match::find_image_in_mat_coord;
+use crate::image_match_error::ImageMatchError;
+use crate::screenshot::rgba_to_mat;
+use crate::screenshot_error::ScreenshotError;
+
+/// 单个显示器的截图及其在虚拟桌面中的原点
+///
+/// 对非主显示器截图时，找图/找色得到的坐标是相对于该显示器的，
+/// 而移动共享的鼠标需要虚拟桌面的全局坐标。`MonitorContext` 统一处理这一偏移，
+/// 所有查找方法返回的都是全局坐标
+///
+/// # 示例
+/// ```rust
+/// use image_utils::monitor_context::MonitorContext;
+///
+/// let ctx = MonitorContext::capture(1)?;
+/// if let Some((x, y)) = ctx.find_image("button.png", 0.8, true)? {
+///     println!("在副屏找到按钮，全局坐标: ({}, {})", x, y);
+/// }
+/// ```
+pub struct MonitorContext {
+    index: usize,
+    origin: (i32, i32),
+    frame: opencv::core::Mat,
+}
+
+impl MonitorContext {
+    /// 截取指定显示器的完整画面
+    ///
+    /// # 参数
+    /// - `index`: 显示器序号（与 `xcap::Monitor::all()` 的顺序一致）
+    pub fn capture(index: usize) -> Result<Self, ScreenshotError> {
+        let monitors = Monitor::all()?;
+        if monitors.is_empty() {
+            return Err(ScreenshotError::NoMonitorFound);
+        }
+
+        let monitor = monitors.get(index).ok_or(ScreenshotError::MonitorIndexOutOfRange {
+            index,
+            count: monitors.len(),
+        })?;
+        let origin = (monitor.x()?, monitor.y()?);
+        let image = monitor.capture_image()?;
+
+        // 转换为 BGR
+        let frame = rgba_to_mat(&image, opencv::imgproc::COLOR_RGBA2BGR)?;
+
+        Ok(Self { index, origin, frame })
+    }
+
+    /// 重新截取该显示器的画面
+    pub fn refresh(&mut self) -> Result<(), ScreenshotError> {
+        *self = Self::capture(self.index)?;
+        Ok(())
+    }
+
+    /// 显示器序号
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// 显示器左上角在虚拟桌面中的全局坐标
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    /// 截取到的画面（BGR 格式）
+    pub fn frame(&self) -> &opencv::core::Mat {
+        &self.frame
+    }
+
+    /// 将相对于该显示器的坐标转换为全局坐标
+    pub fn to_global(&self, x: i32, y: i32) -> (i32, i32) {
+        (self.origin.0 + x, self.origin.1 + y)
+    }
+
+    /// 在该显示器画面中找图
+    ///
+    /// # 参数
+    /// - `image_path`: 模板图片路径
+    /// - `threshold`: 相似度阈值
+    /// - `rgb`: 是否使用彩色匹配
+    ///
+    /// # 返回
+    /// 置信度最高的匹配中心点的全局坐标，未找到返回 None
+    pub fn find_image(
+        &self,
+        image_path: &str,
+        threshold: f64,
+        rgb: bool,
+    ) -> Result<Option<(i32, i32)>, ImageMatchError> {
+        let found = find_image_in_mat_coord(&self.frame, image_path, threshold, rgb)?;
+        Ok(found.map(|(x, y)| self.to_global(x, y)))
+    }
+
+    /// 在该显示器画面中找色
+    ///
+    /// # 参数
+    /// - `target_rgb`: 目标颜色 (R, G, B)
+    /// - `tolerance`: 容差值
+    ///
+    /// # 返回
+    /// 第一个匹配像素的全局坐标，未找到返回 None
+    pub fn find_color(
+        &self,
+        target_rgb: (u8, u8, u8),
+        tolerance: u32,
+    ) -> anyhow::Result<Option<(i32, i32)>> {
+        let found = find_color_in_mat_coord(&self.frame, target_rgb, tolerance, (0, 0))?;
+        Ok(found.map(|(x, y)| self.to_global(x as i32, y as i32)))
+    }
+}
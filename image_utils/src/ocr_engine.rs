@@ -0,0 +1,140 @@
+//! 可复用的图库找字引擎
+
+use std::path::Path;
+use opencv::imgproc;
+use rayon::prelude::*;
+use crate::image_match::{extract_matches, read_image, run_match, suppress_overlapping_characters, to_gray_mat};
+use crate::image_match_error::ImageMatchError;
+use crate::screenshot::capture_into;
+use crate::types::MatchResult;
+
+/// 图库找字引擎
+///
+/// `find_characters_from_library_threaded` 每次调用都要重新读取图库并截图。
+/// `OcrEngine` 在构造时一次性读取所有字符模板并转换为灰度，
+/// 之后每次 `recognize` 只需截图和匹配，截图缓冲区也会被复用
+///
+/// # 示例
+/// ```rust
+/// use image_utils::ocr_engine::OcrEngine;
+///
+/// let mut engine = OcrEngine::from_digit_library("C:\\path\\to\\library")?;
+/// loop {
+///     let text = engine.recognize(100, 100, 200, 40, 0.9)?;
+///     println!("识别结果: {}", text);
+/// }
+/// ```
+pub struct OcrEngine {
+    /// (字符, 灰度模板)
+    templates: Vec<(char, opencv::core::Mat)>,
+    /// 灰度截图缓冲区，区域尺寸不变时复用
+    frame: opencv::core::Mat,
+}
+
+impl OcrEngine {
+    /// 根据字符与模板图片的对应关系创建引擎
+    ///
+    /// # 参数
+    /// - `charset`: (字符, 模板图片路径) 列表
+    ///
+    /// # 返回
+    /// 任一模板读取失败时返回错误
+    pub fn new(charset: &[(char, &str)]) -> Result<Self, ImageMatchError> {
+        let templates = charset
+            .iter()
+            .map(|&(ch, path)| Ok((ch, to_gray_mat(&read_image(path)?)?)))
+            .collect::<Result<Vec<_>, ImageMatchError>>()?;
+
+        Ok(Self {
+            templates,
+            frame: opencv::core::Mat::default(),
+        })
+    }
+
+    /// 从数字图库创建引擎（图库文件夹中包含 0.bmp 到 9.bmp）
+    ///
+    /// 与 `find_characters_from_library_threaded` 一致，缺失的数字模板会被跳过
+    ///
+    /// # 参数
+    /// - `library_path`: 图库路径
+    pub fn from_digit_library(library_path: &str) -> Result<Self, ImageMatchError> {
+        let mut templates = Vec::with_capacity(10);
+        for digit in 0..10u8 {
+            let template_path = Path::new(library_path).join(format!("{}.bmp", digit));
+            let Some(template_path_str) = template_path.to_str() else {
+                continue;
+            };
+
+            // 如果文件不存在，跳过
+            if let Ok(template) = read_image(template_path_str) {
+                templates.push((char::from(b'0' + digit), to_gray_mat(&template)?));
+            }
+        }
+
+        Ok(Self {
+            templates,
+            frame: opencv::core::Mat::default(),
+        })
+    }
+
+    /// 已加载的字符数量
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// 是否没有加载任何字符
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// 在指定区域中识别字符
+    ///
+    /// # 参数
+    /// - `x`: 截图区域左上角 X 坐标
+    /// - `y`: 截图区域左上角 Y 坐标
+    /// - `width`: 截图宽度
+    /// - `height`: 截图高度
+    /// - `threshold`: 相似度阈值（默认 0.9）
+    ///
+    /// # 返回
+    /// 识别到的字符串（按从左到右的顺序）；截图失败时返回错误，
+    /// 单个模板匹配失败（如模板比区域还大）时跳过该字符
+    pub fn recognize(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        threshold: f64,
+    ) -> Result<String, ImageMatchError> {
+        capture_into(x as u32, y as u32, width, height, imgproc::COLOR_RGBA2GRAY, &mut self.frame)?;
+
+        let frame = &self.frame;
+        // 与 `find_characters_from_library_threaded` 一致，匹配失败的模板（如比区域还大）直接跳过
+        let results: Vec<(char, MatchResult<i32>)> = self
+            .templates
+            .par_iter()
+            .flat_map(|(ch, template)| {
+                let matches = match run_match(frame, template, false, None)
+                    .and_then(|result_mat| extract_matches(&result_mat, template, threshold))
+                {
+                    Ok(m) => m,
+                    Err(_) => return Vec::new(),
+                };
+
+                matches
+                    .into_iter()
+                    .map(|match_result| (*ch, match_result))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        // 去掉同一位置的重复匹配后按 X 坐标排序
+        let mut sorted_results = suppress_overlapping_characters(results);
+        sorted_results.sort_by(|a, b| {
+            a.1.result.x().partial_cmp(b.1.result.x()).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Ok(sorted_results.into_iter().map(|(ch, _)| ch).collect())
+    }
+}
@@ -1,21 +1,27 @@
 
-use ndarray::Array3;
-use opencv::core::MatTrait;
+use std::path::Path;
+use ndarray::{Array3, ArrayBase, Data, Ix3};
+use opencv::core::MatTraitConst;
 use opencv::imgcodecs;
 use xcap::image::{RgbImage, RgbaImage};
 use crate::consts::DEFAULT_ALGORITHM_HINT;
+use crate::types::SaveOptions;
 
-pub fn save_array3_fast(array: &Array3<u8>, path: &str) -> anyhow::Result<()> {
+/// 保存图像（通过 `image` 库编码，格式由扩展名决定）
+///
+/// # 参数
+/// - `array`: 图像数据，形状为 [height, width, channels]，RGB（3通道）或 RGBA（4通道）；
+///   可以是切片或转置后的视图，非连续内存时会先复制为标准布局
+/// - `path`: 保存路径
+pub fn save_array3_fast<S: Data<Elem = u8>>(array: &ArrayBase<S, Ix3>, path: &str) -> anyhow::Result<()> {
     let (height, width, channels) = (
         array.shape()[0],
         array.shape()[1],
         array.shape()[2],
     );
 
-    // 确保数据是连续的
-    if !array.is_standard_layout() {
-        anyhow::bail!("数组不是标准布局，无法直接保存");
-    }
+    // 连续数组直接借用底层数据，切片或转置的视图复制为标准布局
+    let array = array.as_standard_layout();
 
     match channels {
         3 => {
@@ -35,55 +41,203 @@ pub fn save_array3_fast(array: &Array3<u8>, path: &str) -> anyhow::Result<()> {
         _ => anyhow::bail!("不支持的通道数: {}", channels),
     }
 
-    println!("图像已保存: {}", path);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(path, "图像已保存");
     Ok(())
 }
 
+/// 通过 OpenCV 保存图像
+///
+/// # 参数
+/// - `array`: 图像数据，形状为 [height, width, channels]，RGB（3通道）或 RGBA（4通道），
+///   与 `screenshot_to_ndarray` 等函数的输出一致
+/// - `filename`: 保存路径，格式由扩展名决定
+///
+/// # 注意
+/// OpenCV 的 `imwrite` 要求 BGR 顺序，这里会先转换通道顺序；RGBA 输入会丢弃 Alpha 通道
 pub fn save_array3_via_opencv(array: &Array3<u8>, filename: &str) -> anyhow::Result<()> {
-    let (height, width, channels) = (
-        array.shape()[0] as i32,
-        array.shape()[1] as i32,
-        array.shape()[2],
-    );
+    // RGB 和 RGBA 都转换为 BGR 再保存（OpenCV 的 imwrite 对 RGBA 支持不好）
+    let mat = array3_to_mat(array, opencv::imgproc::COLOR_RGBA2BGR)?;
 
-    // 手动创建 Mat 并填充数据
-    let mut mat = unsafe {opencv::core::Mat::new_rows_cols(height, width, opencv::core::CV_8UC(channels as i32))?};
-
-    // 手动复制数据
-    for y in 0..height {
-        for x in 0..width {
-            match channels {
-                3 => {
-                    let b = array[[y as usize, x as usize, 0]];
-                    let g = array[[y as usize, x as usize, 1]];
-                    let r = array[[y as usize, x as usize, 2]];
-                    *mat.at_2d_mut::<opencv::core::Vec3b>(y, x)? =
-                        opencv::core::Vec3b::from([b, g, r]);
-                }
-                4 => {
-                    let b = array[[y as usize, x as usize, 0]];
-                    let g = array[[y as usize, x as usize, 1]];
-                    let r = array[[y as usize, x as usize, 2]];
-                    let a = array[[y as usize, x as usize, 3]];
-                    *mat.at_2d_mut::<opencv::core::Vec4b>(y, x)? =
-                        opencv::core::Vec4b::from([b, g, r, a]);
+    if !imgcodecs::imwrite(filename, &mat, &opencv::core::Vector::new())? {
+        anyhow::bail!("通过 OpenCV 保存图像失败: {}", filename);
+    }
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(filename, "通过 OpenCV 保存");
+    Ok(())
+}
+
+/// 保存为 WebP（通过 OpenCV 编码，可控制质量）
+///
+/// # 参数
+/// - `array`: 图像数据，形状为 [height, width, channels]，RGB（3通道）或 RGBA（4通道）
+/// - `path`: 保存路径（建议以 .webp 结尾）
+/// - `quality`: 压缩质量，范围 1-100，越大画质越好、文件越大
+///
+/// # 示例
+/// ```rust
+/// use image_utils::saving::save_array3_webp;
+/// use image_utils::screenshot::screenshot_to_ndarray;
+///
+/// let img = screenshot_to_ndarray(0, 0, 800, 600)?;
+/// save_array3_webp(&img, "./screen.webp", 80.0)?;
+/// ```
+pub fn save_array3_webp(array: &Array3<u8>, path: &str, quality: f32) -> anyhow::Result<()> {
+    if !(1.0..=100.0).contains(&quality) {
+        anyhow::bail!("WebP 质量必须在 1-100 之间: {}", quality);
+    }
+
+    let mat = array3_to_bgr_mat(array)?;
+
+    let mut params = opencv::core::Vector::<i32>::new();
+    params.push(imgcodecs::IMWRITE_WEBP_QUALITY);
+    params.push(quality.round() as i32);
+
+    if !imgcodecs::imwrite(path, &mat, &params)? {
+        anyhow::bail!("保存 WebP 图像失败: {}", path);
+    }
+    Ok(())
+}
+
+/// 按指定的编码选项保存图像（通过 OpenCV）
+///
+/// 根据文件扩展名选择要传给 `imwrite` 的参数：.jpg/.jpeg 使用 `jpeg_quality`，
+/// .png 使用 `png_compression`，其他格式使用 OpenCV 的默认设置
+///
+/// # 参数
+/// - `array`: 图像数据，形状为 [height, width, channels]，RGB（3通道）或 RGBA（4通道）
+/// - `path`: 保存路径
+/// - `options`: 编码选项
+///
+/// # 返回
+/// 质量或压缩级别超出范围时返回错误；保存为 JPEG 时 RGBA 输入会丢弃 Alpha 通道
+///
+/// # 示例
+/// ```rust
+/// use image_utils::saving::save_array3_with_options;
+/// use image_utils::screenshot::screenshot_to_ndarray;
+/// use image_utils::types::SaveOptions;
+///
+/// let img = screenshot_to_ndarray(0, 0, 800, 600)?;
+/// // 快速保存，几乎不压缩
+/// save_array3_with_options(&img, "./screen.png", SaveOptions { png_compression: Some(0), ..Default::default() })?;
+/// // 存档用 JPEG
+/// save_array3_with_options(&img, "./screen.jpg", SaveOptions { jpeg_quality: Some(85), ..Default::default() })?;
+/// ```
+pub fn save_array3_with_options(array: &Array3<u8>, path: &str, options: SaveOptions) -> anyhow::Result<()> {
+    if let Some(quality) = options.jpeg_quality.filter(|&quality| quality > 100) {
+        anyhow::bail!("JPEG 质量必须在 0-100 之间: {}", quality);
+    }
+    if let Some(compression) = options.png_compression.filter(|&compression| compression > 9) {
+        anyhow::bail!("PNG 压缩级别必须在 0-9 之间: {}", compression);
+    }
+
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let mut mat = array3_to_bgr_mat(array)?;
+    let mut params = opencv::core::Vector::<i32>::new();
+
+    match extension.as_str() {
+        "jpg" | "jpeg" => {
+            // JPEG 不支持 Alpha 通道
+            if mat.channels() == 4 {
+                let mut bgr_mat = opencv::core::Mat::default();
+                opencv::imgproc::cvt_color(&mat, &mut bgr_mat, opencv::imgproc::COLOR_BGRA2BGR, 0, DEFAULT_ALGORITHM_HINT)?;
+                mat = bgr_mat;
+            }
+            if let Some(quality) = options.jpeg_quality {
+                params.push(imgcodecs::IMWRITE_JPEG_QUALITY);
+                params.push(quality as i32);
+            }
+        }
+        "png" => {
+            if let Some(compression) = options.png_compression {
+                params.push(imgcodecs::IMWRITE_PNG_COMPRESSION);
+                params.push(compression as i32);
+            }
+        }
+        _ => {}
+    }
+
+    if !imgcodecs::imwrite(path, &mat, &params)? {
+        anyhow::bail!("保存图像失败: {}", path);
+    }
+    Ok(())
+}
+
+/// 将图像编码为内存中的字节（不写文件），用于通过网络发送截图等场景
+///
+/// # 参数
+/// - `mat`: 图像（BGR、BGRA 或灰度，与 `screenshot_to_mat` 的输出一致）
+/// - `format`: 编码格式的扩展名，支持 ".png"、".jpg"/".jpeg"（不区分大小写）
+/// - `jpeg_quality`: JPEG 质量，范围 0-100，None 时使用 OpenCV 默认值（95）；PNG 忽略该参数
+///
+/// # 返回
+/// 编码后的文件内容
+///
+/// # 示例
+/// ```rust
+/// use image_utils::saving::encode_mat_to_bytes;
+/// use image_utils::screenshot::screenshot_to_mat;
+///
+/// let img = screenshot_to_mat(0, 0, 800, 600)?;
+/// let bytes = encode_mat_to_bytes(&img, ".jpg", Some(80))?;
+/// ```
+pub fn encode_mat_to_bytes(
+    mat: &opencv::core::Mat,
+    format: &str,
+    jpeg_quality: Option<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    let mut params = opencv::core::Vector::<i32>::new();
+
+    match format.to_ascii_lowercase().as_str() {
+        ".png" => {}
+        ".jpg" | ".jpeg" => {
+            if let Some(quality) = jpeg_quality {
+                if quality > 100 {
+                    anyhow::bail!("JPEG 质量必须在 0-100 之间: {}", quality);
                 }
-                _ => anyhow::bail!("不支持的通道数: {}", channels),
+                params.push(imgcodecs::IMWRITE_JPEG_QUALITY);
+                params.push(quality as i32);
             }
         }
+        _ => anyhow::bail!("不支持的编码格式: {}", format),
+    }
+
+    let mut buffer = opencv::core::Vector::<u8>::new();
+    if !imgcodecs::imencode(format, mat, &mut buffer, &params)? {
+        anyhow::bail!("编码图像失败: {}", format);
     }
+    Ok(buffer.to_vec())
+}
+
+/// 将 RGB/RGBA 的 Array3 转换为 OpenCV 使用的 BGR/BGRA Mat
+fn array3_to_bgr_mat(array: &Array3<u8>) -> anyhow::Result<opencv::core::Mat> {
+    array3_to_mat(array, opencv::imgproc::COLOR_RGBA2BGRA)
+}
 
-    println!("保存之前1");
-    // 如果是 RGBA，转换为 BGR 再保存（OpenCV 的 imwrite 对 RGBA 支持不好）
-    let save_mat = if channels == 4 {
-        let mut bgr_mat = opencv::core::Mat::default();
-        opencv::imgproc::cvt_color(&mat, &mut bgr_mat, opencv::imgproc::COLOR_RGBA2BGR, 0, DEFAULT_ALGORITHM_HINT)?;
-        bgr_mat
-    } else {
-        mat
+/// 将 RGB/RGBA 的 Array3 转换为 Mat，RGB 输入转换为 BGR，RGBA 输入按 `rgba_code` 转换
+fn array3_to_mat(array: &Array3<u8>, rgba_code: i32) -> anyhow::Result<opencv::core::Mat> {
+    let (height, channels) = (array.shape()[0], array.shape()[2]);
+
+    let code = match channels {
+        3 => opencv::imgproc::COLOR_RGB2BGR,
+        4 => rgba_code,
+        _ => anyhow::bail!("不支持的通道数: {}", channels),
     };
 
-    imgcodecs::imwrite(filename, &save_mat, &opencv::core::Vector::new())?;
-    println!("通过 OpenCV 保存: {}", filename);
-    Ok(())
-}
\ No newline at end of file
+    // 非标准布局时复制为连续内存
+    let array = array.as_standard_layout();
+    let data = array.as_slice().unwrap();
+    let mat = opencv::core::Mat::from_slice(data)?;
+    let mat = mat.reshape(channels as i32, height as i32)?;
+
+    let mut bgr_mat = opencv::core::Mat::default();
+    opencv::imgproc::cvt_color(&mat, &mut bgr_mat, code, 0, DEFAULT_ALGORITHM_HINT)?;
+    Ok(bgr_mat)
+}
@@ -0,0 +1,119 @@
+//! 截图一次、多次查询的屏幕帧
+
+use crate::color_detection::find_color_in_mat_coord;
+use crate::image_match::{find_image_in_mat, find_image_in_mat_coord};
+use crate::image_match_error::ImageMatchError;
+use crate::screenshot::screenshot_to_mat;
+use crate::screenshot_error::ScreenshotError;
+
+/// 一次截图及其在屏幕上的偏移
+///
+/// 找色、找图等函数每次调用都会重新截图。同一帧画面上要做多次检查时，
+/// 先用 `ScreenFrame::capture` 截图一次，再在这张截图上查询，
+/// 所有查找方法返回的都是已经加上偏移的屏幕绝对坐标。
+/// 也可以用 `ScreenFrame::from_mat` 从固定图片构造，便于测试
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screen_frame::ScreenFrame;
+///
+/// let frame = ScreenFrame::capture(100, 100, 800, 600)?;
+/// if frame.find_image("ok.png", 0.8, true)? {
+///     let pos = frame.find_image_coord("ok.png", 0.8, true)?;
+/// }
+/// let red = frame.find_color((255, 0, 0), 10)?;
+/// ```
+pub struct ScreenFrame {
+    offset: (i32, i32),
+    frame: opencv::core::Mat,
+}
+
+impl ScreenFrame {
+    /// 截取屏幕区域（BGR 格式）
+    ///
+    /// # 参数
+    /// - `x`: 截图区域左上角 X 坐标
+    /// - `y`: 截图区域左上角 Y 坐标
+    /// - `width`: 截图宽度
+    /// - `height`: 截图高度
+    pub fn capture(x: i32, y: i32, width: u32, height: u32) -> Result<Self, ScreenshotError> {
+        let frame = screenshot_to_mat(x as u32, y as u32, width, height)?;
+
+        Ok(Self { offset: (x, y), frame })
+    }
+
+    /// 由已有图像创建（如读取的测试图片）
+    ///
+    /// # 参数
+    /// - `frame`: 图像（OpenCV Mat，BGR 格式）
+    /// - `offset`: 图像左上角对应的屏幕坐标 (x, y)
+    pub fn from_mat(frame: opencv::core::Mat, offset: (i32, i32)) -> Self {
+        Self { offset, frame }
+    }
+
+    /// 截图左上角的屏幕坐标
+    pub fn offset(&self) -> (i32, i32) {
+        self.offset
+    }
+
+    /// 截取到的画面（BGR 格式）
+    pub fn frame(&self) -> &opencv::core::Mat {
+        &self.frame
+    }
+
+    /// 将相对于截图的坐标转换为屏幕绝对坐标
+    pub fn to_absolute(&self, x: i32, y: i32) -> (i32, i32) {
+        (self.offset.0 + x, self.offset.1 + y)
+    }
+
+    /// 在截图中找色
+    ///
+    /// # 参数
+    /// - `target_rgb`: 目标颜色 (R, G, B)
+    /// - `tolerance`: 容差值
+    ///
+    /// # 返回
+    /// 第一个匹配像素的屏幕绝对坐标，未找到返回 None
+    pub fn find_color(
+        &self,
+        target_rgb: (u8, u8, u8),
+        tolerance: u32,
+    ) -> anyhow::Result<Option<(i32, i32)>> {
+        let found = find_color_in_mat_coord(&self.frame, target_rgb, tolerance, (0, 0))?;
+        Ok(found.map(|(x, y)| self.to_absolute(x as i32, y as i32)))
+    }
+
+    /// 在截图中找图 - 只返回是否找到
+    ///
+    /// # 参数
+    /// - `image_path`: 模板图片路径
+    /// - `threshold`: 相似度阈值
+    /// - `rgb`: 是否使用彩色匹配
+    pub fn find_image(
+        &self,
+        image_path: &str,
+        threshold: f64,
+        rgb: bool,
+    ) -> Result<bool, ImageMatchError> {
+        find_image_in_mat(&self.frame, image_path, threshold, rgb)
+    }
+
+    /// 在截图中找图 - 返回第一个匹配的中心点坐标
+    ///
+    /// # 参数
+    /// - `image_path`: 模板图片路径
+    /// - `threshold`: 相似度阈值
+    /// - `rgb`: 是否使用彩色匹配
+    ///
+    /// # 返回
+    /// 匹配中心点的屏幕绝对坐标，未找到返回 None
+    pub fn find_image_coord(
+        &self,
+        image_path: &str,
+        threshold: f64,
+        rgb: bool,
+    ) -> Result<Option<(i32, i32)>, ImageMatchError> {
+        let found = find_image_in_mat_coord(&self.frame, image_path, threshold, rgb)?;
+        Ok(found.map(|(x, y)| self.to_absolute(x, y)))
+    }
+}
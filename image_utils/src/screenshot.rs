@@ -1,18 +1,19 @@
 use ndarray::{Array, Array3};
 use opencv::prelude::*;
 use xcap::{Monitor};
+use xcap::image::RgbaImage;
 use crate::consts::DEFAULT_ALGORITHM_HINT;
 use crate::screenshot_error::ScreenshotError;
+use crate::types::{MonitorInfo, Region};
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
 pub fn screenshot_to_ndarray(
     x: u32,
     y: u32,
     width: u32,
     height: u32,
 ) -> Result<Array3<u8>, ScreenshotError> {
-    let monitors = Monitor::all()?;
-    let monitor = &monitors[0];
-    let image = monitor.capture_region(x, y, width, height)?;
+    let image = capture_rgba(x, y, width, height)?;
 
     let img_width = image.width() as usize;
     let img_height = image.height() as usize;
@@ -24,6 +25,41 @@ pub fn screenshot_to_ndarray(
 
 }
 
+/// 截图并返回 RGB 三通道的 ndarray（去掉 Alpha 通道）
+///
+/// 适用于需要 HWC 布局 RGB 输入的机器学习模型，返回的数组是连续的标准布局，
+/// 可以直接交给张量库而无需再复制
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X 坐标
+/// - `y`: 截图区域左上角 Y 坐标
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+///
+/// # 返回
+/// 形状为 [height, width, 3] 的 RGB 数组
+pub fn screenshot_to_rgb_ndarray(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<Array3<u8>, ScreenshotError> {
+    let image = capture_rgba(x, y, width, height)?;
+
+    let img_width = image.width() as usize;
+    let img_height = image.height() as usize;
+
+    // 逐像素丢弃 Alpha 通道
+    let mut rgb_data = Vec::with_capacity(img_width * img_height * 3);
+    for pixel in image.as_raw().chunks_exact(4) {
+        rgb_data.extend_from_slice(&pixel[..3]);
+    }
+
+    let array = Array::from_shape_vec((img_height, img_width, 3), rgb_data)?;
+
+    Ok(array)
+}
+
 /// 快速截图（不保存路径）
 ///
 /// # 参数
@@ -41,39 +77,163 @@ pub fn screenshot_to_ndarray(
 ///
 /// let img = screenshot_fast_no_save(100, 100, 800, 600)?;
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
 pub fn screenshot_to_mat(
     x: u32,
     y: u32,
     width: u32,
     height: u32,
 ) -> Result<opencv::core::Mat, ScreenshotError> {
+    let image = capture_rgba(x, y, width, height)?;
+
+    // 转换为 BGR
+    rgba_to_mat(&image, opencv::imgproc::COLOR_RGBA2BGR)
+}
+
+/// 按区域截图（BGR 格式）
+///
+/// 与 `screenshot_to_mat` 相同，区域由 `Region` 指定，避免混淆角点坐标与宽高
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::screenshot_to_mat_region;
+/// use image_utils::types::Region;
+///
+/// let region = Region::from_corners(100, 100, 300, 250)?;
+/// let img = screenshot_to_mat_region(region)?;
+/// ```
+pub fn screenshot_to_mat_region(region: Region) -> Result<opencv::core::Mat, ScreenshotError> {
+    screenshot_to_mat(region.x, region.y, region.width, region.height)
+}
+
+/// 将区域裁剪到主显示器范围内
+///
+/// 截图函数在区域超出显示器时返回 `RegionOutOfBounds`，
+/// 希望自动裁剪而不是报错的调用方可以先调用本函数
+///
+/// # 返回
+/// 裁剪后的区域；左上角已在显示器外时返回 `RegionOutOfBounds`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::{clamp_region, screenshot_to_mat_region};
+/// use image_utils::types::Region;
+///
+/// let region = clamp_region(Region::from_xywh(1800, 1000, 400, 400))?;
+/// let img = screenshot_to_mat_region(region)?;
+/// ```
+pub fn clamp_region(region: Region) -> Result<Region, ScreenshotError> {
     let monitors = Monitor::all()?;
-    if monitors.is_empty() {
-        return Err(ScreenshotError::NoMonitorFound);
-    }
+    let monitor = monitors.first().ok_or(ScreenshotError::NoMonitorFound)?;
+    let monitor_size = (monitor.width()?, monitor.height()?);
 
-    let monitor = &monitors[0];
-    let image = monitor.capture_region(x, y, width, height)?;
+    region
+        .clamp_to(monitor_size.0, monitor_size.1)
+        .ok_or(ScreenshotError::RegionOutOfBounds { requested: region, monitor: monitor_size })
+}
 
-    // let img_width = image.width() as i32;
-    let img_height = image.height() as i32;
-    let rgba_data = image.into_vec();
+/// 截图到调用方提供的 Mat 中（BGR 格式）
+///
+/// 与 `screenshot_to_mat` 相同，但输出写入 `dst`：尺寸不变时复用其内存，
+/// 只在区域大小变化时重新分配，适合高帧率的连续截图循环
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X 坐标
+/// - `y`: 截图区域左上角 Y 坐标
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `dst`: 输出图像，调用结束后为 BGR 三通道
+///
+/// # 示例
+/// ```rust
+/// use opencv::core::Mat;
+/// use image_utils::screenshot::screenshot_into;
+///
+/// let mut frame = Mat::default();
+/// loop {
+///     screenshot_into(0, 0, 1920, 1080, &mut frame)?;
+///     // 处理 frame ...
+/// }
+/// ```
+pub fn screenshot_into(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    dst: &mut Mat,
+) -> Result<(), ScreenshotError> {
+    capture_into(x, y, width, height, opencv::imgproc::COLOR_RGBA2BGR, dst)
+}
 
-    // 创建 RGBA Mat（OpenCV 内部会优化，from_slice 和 reshape 的开销很小）
-    let mat = opencv::core::Mat::from_slice(&rgba_data)?;
-    let mat = mat.reshape(4, img_height)?; // 4 通道 (RGBA)
+/// 截图并按 `code` 转换到已有的 Mat 中，供需要复用输出缓冲区的调用方使用
+pub(crate) fn capture_into(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    code: i32,
+    dst: &mut Mat,
+) -> Result<(), ScreenshotError> {
+    let image = capture_rgba(x, y, width, height)?;
 
-    // 转换为 BGR
-    let mut bgr_mat = opencv::core::Mat::default();
-    opencv::imgproc::cvt_color(
-        &mat,
-        &mut bgr_mat,
-        opencv::imgproc::COLOR_RGBA2BGR,
-        0,
-        DEFAULT_ALGORITHM_HINT
-    )?;
-    
-    Ok(bgr_mat)
+    rgba_into_mat(&image, code, dst)
+}
+
+/// 按显示器分辨率的比例截图（BGR 格式）
+///
+/// 各参数为 0.0-1.0 的比例，乘以主显示器的分辨率得到像素区域，
+/// 同一套配置可以在 1080p 和 1440p 等不同分辨率下通用。
+/// 区域必须完全位于显示器内（`x_frac + w_frac <= 1` 且 `y_frac + h_frac <= 1`），
+/// 四舍五入造成的 1 像素越界会被截断到显示器边缘
+///
+/// # 参数
+/// - `x_frac`: 区域左上角 X 占显示器宽度的比例
+/// - `y_frac`: 区域左上角 Y 占显示器高度的比例
+/// - `w_frac`: 区域宽度占显示器宽度的比例
+/// - `h_frac`: 区域高度占显示器高度的比例
+///
+/// # 返回
+/// 返回 OpenCV Mat 格式的图像（BGR 格式）；任一比例不在 [0, 1] 内、
+/// 或区域超出显示器右侧/底部时返回 `InvalidFraction`，换算后宽或高为 0 时返回 `InvalidRegion`
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::screenshot_to_mat_relative;
+///
+/// // 截取屏幕右下角四分之一
+/// let img = screenshot_to_mat_relative(0.5, 0.5, 0.5, 0.5)?;
+/// ```
+pub fn screenshot_to_mat_relative(
+    x_frac: f64,
+    y_frac: f64,
+    w_frac: f64,
+    h_frac: f64,
+) -> Result<opencv::core::Mat, ScreenshotError> {
+    for frac in [x_frac, y_frac, w_frac, h_frac] {
+        if !(0.0..=1.0).contains(&frac) {
+            return Err(ScreenshotError::InvalidFraction(frac));
+        }
+    }
+    for end in [x_frac + w_frac, y_frac + h_frac] {
+        if end > 1.0 {
+            return Err(ScreenshotError::InvalidFraction(end));
+        }
+    }
+
+    let monitors = Monitor::all()?;
+    let monitor = monitors.first().ok_or(ScreenshotError::NoMonitorFound)?;
+    let monitor_width = monitor.width()?;
+    let monitor_height = monitor.height()?;
+
+    let x = (x_frac * monitor_width as f64).round() as u32;
+    let y = (y_frac * monitor_height as f64).round() as u32;
+    let width = ((w_frac * monitor_width as f64).round() as u32).min(monitor_width - x);
+    let height = ((h_frac * monitor_height as f64).round() as u32).min(monitor_height - y);
+    if width == 0 || height == 0 {
+        return Err(ScreenshotError::InvalidRegion { x1: x, y1: y, x2: x + width, y2: y + height });
+    }
+
+    screenshot_to_mat(x, y, width, height)
 }
 
 /// 截图并直接转换为灰度图（优化版，避免 BGR 中间转换）
@@ -86,39 +246,17 @@ pub fn screenshot_to_mat(
 ///
 /// # 返回
 /// 返回 OpenCV Mat 格式的灰度图像（单通道）
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", err))]
 pub fn screenshot_to_mat_gray(
     x: u32,
     y: u32,
     width: u32,
     height: u32,
 ) -> Result<opencv::core::Mat, ScreenshotError> {
-    let monitors = Monitor::all()?;
-    if monitors.is_empty() {
-        return Err(ScreenshotError::NoMonitorFound);
-    }
-
-    let monitor = &monitors[0];
-    let image = monitor.capture_region(x, y, width, height)?;
-
-    // let img_width = image.width() as i32;
-    let img_height = image.height() as i32;
-    let rgba_data = image.into_vec();
-
-    // 创建 RGBA Mat（OpenCV 内部会优化，from_slice 和 reshape 的开销很小）
-    let mat = opencv::core::Mat::from_slice(&rgba_data)?;
-    let mat = mat.reshape(4, img_height)?; // 4 通道 (RGBA)
+    let image = capture_rgba(x, y, width, height)?;
 
     // 直接从 RGBA 转换为灰度，避免 BGR 中间转换
-    let mut gray_mat = opencv::core::Mat::default();
-    opencv::imgproc::cvt_color(
-        &mat,
-        &mut gray_mat,
-        opencv::imgproc::COLOR_RGBA2GRAY,
-        0,
-        DEFAULT_ALGORITHM_HINT
-    )?;
-    
-    Ok(gray_mat)
+    rgba_to_mat(&image, opencv::imgproc::COLOR_RGBA2GRAY)
 }
 
 /// 二值化截图
@@ -128,17 +266,21 @@ pub fn screenshot_to_mat_gray(
 /// - `y1`: 截图区域左上角 Y 坐标
 /// - `x2`: 截图区域右下角 X 坐标
 /// - `y2`: 截图区域右下角 Y 坐标
+///
+/// # 返回
+/// 使用 OTSU 阈值二值化后的单通道图像（0 或 255）；
+/// 右下角不在左上角右下方时返回 `InvalidRegion`
 pub fn screenshot_to_mat_binary(
     x1: u32,
     y1: u32,
     x2: u32,
     y2: u32,
 ) -> Result<Mat, ScreenshotError> {
-    let width = x2 - x1;
-    let height = y2 - y1;
+    // 右下角必须在左上角的右下方，避免无符号减法溢出
+    let region = Region::from_corners(x1, y1, x2, y2)?;
 
     // 截图
-    let img = screenshot_to_mat(x1, y1, width, height)?;
+    let img = screenshot_to_mat_region(region)?;
 
     // 转换为灰度图
     let mut gray = opencv::core::Mat::default();
@@ -154,5 +296,288 @@ pub fn screenshot_to_mat_binary(
         opencv::imgproc::THRESH_BINARY | opencv::imgproc::THRESH_OTSU,
     )?;
 
-    Ok(gray)
+    Ok(binary)
+}
+
+/// 按 HSV 范围二值化截图
+///
+/// 截图后转换为 HSV，落在 [`lower_hsv`, `upper_hsv`] 范围内的像素为 255，其余为 0。
+/// 适合按颜色分割界面元素，比灰度图上的 OTSU 二值化更稳定
+///
+/// # 参数
+/// - `x`: 截图区域左上角 X 坐标
+/// - `y`: 截图区域左上角 Y 坐标
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+/// - `lower_hsv`: HSV 下界 (H, S, V)，使用 OpenCV 的取值范围（H: 0-180，S/V: 0-255）
+/// - `upper_hsv`: HSV 上界 (H, S, V)
+///
+/// # 返回
+/// 单通道的二值掩码
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::screenshot_to_mask_hsv;
+///
+/// // 提取红色血条
+/// let mask = screenshot_to_mask_hsv(100, 100, 200, 20, (0, 120, 70), (10, 255, 255))?;
+/// ```
+pub fn screenshot_to_mask_hsv(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    lower_hsv: (u8, u8, u8),
+    upper_hsv: (u8, u8, u8),
+) -> Result<Mat, ScreenshotError> {
+    let img = screenshot_to_mat(x, y, width, height)?;
+
+    // 转换为 HSV
+    let mut hsv = Mat::default();
+    opencv::imgproc::cvt_color(&img, &mut hsv, opencv::imgproc::COLOR_BGR2HSV, 0, DEFAULT_ALGORITHM_HINT)?;
+
+    let lower = opencv::core::Scalar::new(lower_hsv.0 as f64, lower_hsv.1 as f64, lower_hsv.2 as f64, 0.0);
+    let upper = opencv::core::Scalar::new(upper_hsv.0 as f64, upper_hsv.1 as f64, upper_hsv.2 as f64, 0.0);
+
+    let mut mask = Mat::default();
+    opencv::core::in_range(&hsv, &lower, &upper, &mut mask)?;
+
+    Ok(mask)
+}
+
+/// 获取所有显示器的布局信息
+///
+/// # 返回
+/// 每个显示器在虚拟桌面坐标系中的位置、尺寸、缩放比例等信息，顺序与 `xcap::Monitor::all()` 一致
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::monitor_layout;
+///
+/// for info in monitor_layout()? {
+///     println!("{} {}: ({}, {}) {}x{}", info.index, info.name, info.x, info.y, info.width, info.height);
+/// }
+/// ```
+pub fn monitor_layout() -> Result<Vec<MonitorInfo>, ScreenshotError> {
+    let monitors = Monitor::all()?;
+
+    monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            Ok(MonitorInfo {
+                index,
+                name: monitor.name()?,
+                x: monitor.x()?,
+                y: monitor.y()?,
+                width: monitor.width()?,
+                height: monitor.height()?,
+                scale_factor: monitor.scale_factor()?,
+                is_primary: monitor.is_primary()?,
+            })
+        })
+        .collect()
+}
+
+/// 列出所有显示器（序号、名称、位置、分辨率等），用于选择 `_on_monitor` 系列函数的显示器序号
+///
+/// 与 `monitor_layout` 相同
+pub fn list_monitors() -> Result<Vec<MonitorInfo>, ScreenshotError> {
+    monitor_layout()
+}
+
+/// 截取指定显示器上的区域并返回 RGBA 格式的 ndarray
+///
+/// # 参数
+/// - `monitor_index`: 显示器序号（见 `list_monitors`）
+/// - `x`: 截图区域左上角 X 坐标（相对于该显示器）
+/// - `y`: 截图区域左上角 Y 坐标（相对于该显示器）
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+///
+/// # 返回
+/// 形状为 [height, width, 4] 的 RGBA 数组；序号越界时返回 `MonitorIndexOutOfRange`
+pub fn screenshot_to_ndarray_on_monitor(
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<Array3<u8>, ScreenshotError> {
+    let image = capture_rgba_on_monitor(monitor_index, x, y, width, height)?;
+
+    let img_width = image.width() as usize;
+    let img_height = image.height() as usize;
+    let array = Array::from_shape_vec((img_height, img_width, 4), image.into_vec())?;
+
+    Ok(array)
+}
+
+/// 截取指定显示器上的区域（BGR 格式）
+///
+/// # 参数
+/// - `monitor_index`: 显示器序号（见 `list_monitors`）
+/// - `x`: 截图区域左上角 X 坐标（相对于该显示器）
+/// - `y`: 截图区域左上角 Y 坐标（相对于该显示器）
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::screenshot_to_mat_on_monitor;
+///
+/// // 截取副屏左上角
+/// let img = screenshot_to_mat_on_monitor(1, 0, 0, 800, 600)?;
+/// ```
+pub fn screenshot_to_mat_on_monitor(
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<Mat, ScreenshotError> {
+    let image = capture_rgba_on_monitor(monitor_index, x, y, width, height)?;
+
+    rgba_to_mat(&image, opencv::imgproc::COLOR_RGBA2BGR)
+}
+
+/// 截取指定显示器上的区域并转换为灰度图
+///
+/// # 参数
+/// - `monitor_index`: 显示器序号（见 `list_monitors`）
+/// - `x`: 截图区域左上角 X 坐标（相对于该显示器）
+/// - `y`: 截图区域左上角 Y 坐标（相对于该显示器）
+/// - `width`: 截图宽度
+/// - `height`: 截图高度
+pub fn screenshot_to_mat_gray_on_monitor(
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<Mat, ScreenshotError> {
+    let image = capture_rgba_on_monitor(monitor_index, x, y, width, height)?;
+
+    rgba_to_mat(&image, opencv::imgproc::COLOR_RGBA2GRAY)
+}
+
+/// 查找包含指定全局坐标的显示器
+///
+/// # 参数
+/// - `x`: 虚拟桌面全局 X 坐标
+/// - `y`: 虚拟桌面全局 Y 坐标
+///
+/// # 返回
+/// 包含该坐标的显示器序号（与 `xcap::Monitor::all()` 的顺序一致），
+/// 坐标落在任何显示器之外（例如非矩形多屏布局的空隙）时返回 None
+///
+/// # 示例
+/// ```rust
+/// use image_utils::screenshot::monitor_at_point;
+///
+/// if let Some(index) = monitor_at_point(2600, 300)? {
+///     println!("坐标位于第 {} 个显示器", index);
+/// }
+/// ```
+pub fn monitor_at_point(x: i32, y: i32) -> Result<Option<usize>, ScreenshotError> {
+    let monitors = Monitor::all()?;
+
+    for (index, monitor) in monitors.iter().enumerate() {
+        let left = monitor.x()? as i64;
+        let top = monitor.y()? as i64;
+        let right = left + monitor.width()? as i64;
+        let bottom = top + monitor.height()? as i64;
+
+        let (x, y) = (x as i64, y as i64);
+        if x >= left && x < right && y >= top && y < bottom {
+            return Ok(Some(index));
+        }
+    }
+
+    Ok(None)
+}
+
+/// 检查全局坐标是否落在某个显示器上
+///
+/// # 参数
+/// - `x`: 虚拟桌面全局 X 坐标
+/// - `y`: 虚拟桌面全局 Y 坐标
+///
+/// # 返回
+/// 坐标在任意显示器范围内返回 true，否则返回 false
+pub fn point_on_any_monitor(x: i32, y: i32) -> Result<bool, ScreenshotError> {
+    Ok(monitor_at_point(x, y)?.is_some())
+}
+
+/// 截取第一个显示器上的指定区域，返回原始 RGBA 图像
+///
+/// 所有截图函数共用的入口，显示器选择等逻辑只需在这里维护
+fn capture_rgba(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<RgbaImage, ScreenshotError> {
+    capture_rgba_on_monitor(0, x, y, width, height)
+}
+
+/// 截取指定显示器上的区域（坐标相对于该显示器左上角），返回原始 RGBA 图像
+fn capture_rgba_on_monitor(
+    monitor_index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<RgbaImage, ScreenshotError> {
+    let monitors = Monitor::all()?;
+    if monitors.is_empty() {
+        return Err(ScreenshotError::NoMonitorFound);
+    }
+
+    let monitor = monitors.get(monitor_index).ok_or(ScreenshotError::MonitorIndexOutOfRange {
+        index: monitor_index,
+        count: monitors.len(),
+    })?;
+
+    // 超出显示器的区域会被 xcap 静默截断或直接报错，这里提前检查，避免坐标错位
+    let requested = Region::from_xywh(x, y, width, height);
+    let monitor_size = (monitor.width()?, monitor.height()?);
+    if !requested.fits_within(monitor_size.0, monitor_size.1) {
+        return Err(ScreenshotError::RegionOutOfBounds { requested, monitor: monitor_size });
+    }
+
+    Ok(monitor.capture_region(x, y, width, height)?)
+}
+
+/// 将 RGBA 截图转换为 OpenCV Mat
+///
+/// # 参数
+/// - `image`: RGBA 截图
+/// - `code`: `cvt_color` 的转换代码，如 `COLOR_RGBA2BGR`、`COLOR_RGBA2GRAY`
+pub(crate) fn rgba_to_mat(image: &RgbaImage, code: i32) -> Result<Mat, ScreenshotError> {
+    let mut converted = opencv::core::Mat::default();
+    rgba_into_mat(image, code, &mut converted)?;
+
+    Ok(converted)
+}
+
+/// 将 RGBA 截图转换到已有的 Mat 中
+///
+/// `cvt_color` 在 `dst` 尺寸和类型已经匹配时会直接复用其内存，否则重新分配
+fn rgba_into_mat(image: &RgbaImage, code: i32, dst: &mut Mat) -> Result<(), ScreenshotError> {
+    let img_height = image.height() as i32;
+
+    // 创建 RGBA Mat（OpenCV 内部会优化，from_slice 和 reshape 的开销很小）
+    let mat = opencv::core::Mat::from_slice(image.as_raw().as_slice())?;
+    let mat = mat.reshape(4, img_height)?; // 4 通道 (RGBA)
+
+    opencv::imgproc::cvt_color(
+        &mat,
+        dst,
+        code,
+        0,
+        DEFAULT_ALGORITHM_HINT
+    )?;
+
+    Ok(())
 }
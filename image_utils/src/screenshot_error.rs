@@ -1,6 +1,7 @@
 use ndarray::ShapeError;
 use thiserror::Error;
 use xcap::XCapError;
+use crate::types::Region;
 
 #[derive(Debug, Error)]
 pub enum ScreenshotError {
@@ -10,6 +11,21 @@ pub enum ScreenshotError {
     Shape(#[from] ShapeError),
     #[error("未找到任何可用监视器")]
     NoMonitorFound,
+    #[error("监视器序号越界: {index}（共 {count} 个监视器）")]
+    MonitorIndexOutOfRange { index: usize, count: usize },
+    #[error("无效的截图区域: 左上角 ({x1}, {y1})，右下角 ({x2}, {y2})")]
+    InvalidRegion { x1: u32, y1: u32, x2: u32, y2: u32 },
+    #[error("截图区域 {requested:?} 超出显示器范围 {monitor:?}")]
+    RegionOutOfBounds {
+        /// 请求的截图区域（相对于显示器左上角）
+        requested: Region,
+        /// 显示器的 (宽, 高)
+        monitor: (u32, u32),
+    },
+    #[error("屏幕坐标不能为负数: ({x}, {y})")]
+    NegativeCoordinate { x: i32, y: i32 },
+    #[error("相对坐标超出范围 [0, 1]: {0}")]
+    InvalidFraction(f64),
     #[error(transparent)]
     OpenCV(#[from]opencv::Error),
 }
@@ -1,7 +1,11 @@
 //! 类型定义
 
+use std::ops::{Add, Mul, Sub};
+
 use serde::{Serialize, Deserialize};
 
+use crate::screenshot_error::ScreenshotError;
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 pub struct Point<T> {
     x: T,
@@ -22,6 +26,82 @@ impl<T> Point<T> {
     }
 }
 
+impl<T: Copy + Mul<Output = T>> Point<T> {
+    /// 将坐标按比例缩放，用于在缩放后的图像与原图之间转换坐标
+    pub fn scale(&self, factor: T) -> Self {
+        Point {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    /// 逐分量相加，例如把相对截图的坐标加上截图偏移
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+
+    /// 逐分量相减，得到从 `other` 指向自身的位移
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(point: Point<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl Point<i32> {
+    /// 转换为浮点坐标
+    pub fn to_f64(&self) -> Point<f64> {
+        Point {
+            x: self.x as f64,
+            y: self.y as f64,
+        }
+    }
+
+    /// 与另一个点的欧几里得距离（`math_utils::calculate_distance_i32`）
+    pub fn distance(&self, other: &Point<i32>) -> f64 {
+        math_utils::calculate_distance_i32(self.x, self.y, other.x, other.y)
+    }
+}
+
+impl Point<f64> {
+    /// 四舍五入为整数坐标
+    pub fn round_to_i32(&self) -> Point<i32> {
+        Point {
+            x: self.x.round() as i32,
+            y: self.y.round() as i32,
+        }
+    }
+
+    /// 与另一个点的欧几里得距离（`math_utils::calculate_distance`）
+    pub fn distance(&self, other: &Point<f64>) -> f64 {
+        math_utils::calculate_distance(self.x, self.y, other.x, other.y)
+    }
+}
+
 /// 匹配结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchResult<T> {
@@ -33,8 +113,172 @@ pub struct MatchResult<T> {
     pub result: Point<f64>,
 }
 
-/// RGB 颜色
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl<T: Copy + Sub<Output = T>> MatchResult<T> {
+    /// 矩形左上角坐标
+    pub fn top_left(&self) -> Point<T> {
+        self.rectangle[0]
+    }
+
+    /// 矩形宽度（右下角 X - 左上角 X）
+    pub fn width(&self) -> T {
+        self.rectangle[3].x - self.rectangle[0].x
+    }
+
+    /// 矩形高度（右下角 Y - 左上角 Y）
+    pub fn height(&self) -> T {
+        self.rectangle[3].y - self.rectangle[0].y
+    }
+}
+
+impl MatchResult<i32> {
+    /// 转换为 OpenCV 矩形（左上角 + 宽高），用于绘制或裁剪
+    pub fn to_cv_rect(&self) -> opencv::core::Rect {
+        let top_left = self.top_left();
+        opencv::core::Rect::new(top_left.x, top_left.y, self.width(), self.height())
+    }
+
+    /// 计算与另一个匹配结果矩形的交并比（IoU），范围 [0, 1]，用于非极大值抑制
+    ///
+    /// 两个矩形不相交时为 0，完全重合时为 1；面积都为 0 时返回 0
+    pub fn iou(&self, other: &MatchResult<i32>) -> f64 {
+        let (a, b) = (self.to_cv_rect(), other.to_cv_rect());
+
+        let overlap_w = ((a.x + a.width).min(b.x + b.width) - a.x.max(b.x)).max(0) as f64;
+        let overlap_h = ((a.y + a.height).min(b.y + b.height) - a.y.max(b.y)).max(0) as f64;
+        let intersection = overlap_w * overlap_h;
+        let union = (a.width as f64 * a.height as f64) + (b.width as f64 * b.height as f64) - intersection;
+
+        if union <= 0.0 {
+            0.0
+        } else {
+            intersection / union
+        }
+    }
+}
+
+/// 屏幕矩形区域（左上角 + 宽高）
+///
+/// 截图、找色等函数的四个整数参数有的是两个角点、有的是左上角加宽高，容易混用。
+/// 用 `Region` 明确区域的含义，通过 `from_corners` 或 `from_xywh` 构造
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    /// 左上角 X 坐标
+    pub x: u32,
+    /// 左上角 Y 坐标
+    pub y: u32,
+    /// 宽度（像素）
+    pub width: u32,
+    /// 高度（像素）
+    pub height: u32,
+}
+
+impl Region {
+    /// 由左上角和宽高创建区域
+    pub fn from_xywh(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// 由左上角 (x1, y1) 和右下角 (x2, y2) 创建区域（右下角不包含在内）
+    ///
+    /// 右下角不在左上角右下方（宽或高为 0 或负数）时返回 `InvalidRegion`
+    pub fn from_corners(x1: u32, y1: u32, x2: u32, y2: u32) -> Result<Self, ScreenshotError> {
+        match (x2.checked_sub(x1), y2.checked_sub(y1)) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => {
+                Ok(Self::from_xywh(x1, y1, width, height))
+            }
+            _ => Err(ScreenshotError::InvalidRegion { x1, y1, x2, y2 }),
+        }
+    }
+
+    /// 区域面积（像素数）
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// 点是否在区域内（包含左边和上边，不包含右边和下边）
+    pub fn contains_point(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+
+    /// 区域是否完全位于 `bounds_width` x `bounds_height` 的范围内（如显示器分辨率）
+    pub fn fits_within(&self, bounds_width: u32, bounds_height: u32) -> bool {
+        self.x as u64 + self.width as u64 <= bounds_width as u64
+            && self.y as u64 + self.height as u64 <= bounds_height as u64
+    }
+
+    /// 将区域裁剪到 `bounds_width` x `bounds_height` 的范围内
+    ///
+    /// 左上角已经在范围外（裁剪后宽或高为 0）时返回 None
+    pub fn clamp_to(&self, bounds_width: u32, bounds_height: u32) -> Option<Self> {
+        let width = self.width.min(bounds_width.saturating_sub(self.x));
+        let height = self.height.min(bounds_height.saturating_sub(self.y));
+        (width > 0 && height > 0).then(|| Self::from_xywh(self.x, self.y, width, height))
+    }
+}
+
+/// 模板匹配方法（对应 OpenCV 的 `TM_*_NORMED`）
+///
+/// - `CcoeffNormed`、`CcorrNormed`：值越大越相似，阈值是下限
+/// - `SqdiffNormed`：值越小越相似（0 表示完全相同），阈值是上限，适合纯色的扁平界面元素
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MatchMethod {
+    /// 归一化相关系数（默认）
+    #[default]
+    CcoeffNormed,
+    /// 归一化互相关
+    CcorrNormed,
+    /// 归一化平方差
+    SqdiffNormed,
+}
+
+impl MatchMethod {
+    /// 是否值越大表示越相似
+    pub fn higher_is_better(self) -> bool {
+        !matches!(self, MatchMethod::SqdiffNormed)
+    }
+
+    /// 匹配值是否达到阈值（按方法的方向比较）
+    pub fn passes(self, value: f64, threshold: f64) -> bool {
+        if self.higher_is_better() {
+            value >= threshold
+        } else {
+            value <= threshold
+        }
+    }
+
+    /// 对应的 OpenCV 匹配方法常量
+    pub fn to_cv(self) -> i32 {
+        match self {
+            MatchMethod::CcoeffNormed => opencv::imgproc::TM_CCOEFF_NORMED,
+            MatchMethod::CcorrNormed => opencv::imgproc::TM_CCORR_NORMED,
+            MatchMethod::SqdiffNormed => opencv::imgproc::TM_SQDIFF_NORMED,
+        }
+    }
+}
+
+/// 显示器信息（虚拟桌面坐标系）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorInfo {
+    /// 显示器序号（与 `xcap::Monitor::all()` 的顺序一致）
+    pub index: usize,
+    /// 显示器名称
+    pub name: String,
+    /// 左上角在虚拟桌面中的 X 坐标
+    pub x: i32,
+    /// 左上角在虚拟桌面中的 Y 坐标
+    pub y: i32,
+    /// 宽度（像素）
+    pub width: u32,
+    /// 高度（像素）
+    pub height: u32,
+    /// 缩放比例（DPI 缩放，如 1.5 表示 150%）
+    pub scale_factor: f32,
+    /// 是否为主显示器
+    pub is_primary: bool,
+}
+
+/// RGB 颜色（序列化为 `{"r": .., "g": .., "b": ..}`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RgbColor {
     pub r: u8,
     pub g: u8,
@@ -57,6 +301,130 @@ impl RgbColor {
     pub fn to_tuple(self) -> (u8, u8, u8) {
         (self.r, self.g, self.b)
     }
+
+    /// 解析十六进制颜色字符串
+    ///
+    /// 支持 `#RRGGBB`、`RRGGBB` 以及三位简写 `#RGB`、`RGB`（如 `#F80` 等同于 `#FF8800`），不区分大小写
+    ///
+    /// # 示例
+    /// ```rust
+    /// use image_utils::types::RgbColor;
+    ///
+    /// let color = RgbColor::from_hex("#2B2D30")?;
+    /// assert_eq!(color.to_tuple(), (0x2B, 0x2D, 0x30));
+    /// ```
+    pub fn from_hex(s: &str) -> anyhow::Result<RgbColor> {
+        let digits = s.trim().strip_prefix('#').unwrap_or(s.trim());
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!("无效的十六进制颜色: {:?}", s);
+        }
+
+        let channel = |hex: &str| u8::from_str_radix(hex, 16);
+        let color = match digits.len() {
+            6 => RgbColor::new(
+                channel(&digits[0..2])?,
+                channel(&digits[2..4])?,
+                channel(&digits[4..6])?,
+            ),
+            // 三位简写：每一位重复一次
+            3 => RgbColor::new(
+                channel(&digits[0..1])? * 0x11,
+                channel(&digits[1..2])? * 0x11,
+                channel(&digits[2..3])? * 0x11,
+            ),
+            _ => anyhow::bail!("无效的十六进制颜色: {:?}（应为 #RRGGBB 或 #RGB）", s),
+        };
+
+        Ok(color)
+    }
+
+    /// 转换为 `#RRGGBB` 格式的十六进制字符串（大写）
+    pub fn to_hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+
+    /// 与另一个颜色的曼哈顿距离（各通道差值的绝对值之和），范围 [0, 765]
+    ///
+    /// 与 `calculate_color_difference` 一致
+    pub fn distance(&self, other: &RgbColor) -> u32 {
+        self.r.abs_diff(other.r) as u32
+            + self.g.abs_diff(other.g) as u32
+            + self.b.abs_diff(other.b) as u32
+    }
+
+    /// 与另一个颜色的欧几里得距离，范围 [0, 约 441.7]
+    ///
+    /// 多个通道同时有小偏差时比曼哈顿距离更接近人眼感受
+    pub fn distance_euclidean(&self, other: &RgbColor) -> f64 {
+        (self.distance_squared(other) as f64).sqrt()
+    }
+
+    /// 欧几里得距离的平方，用于与整数容差比较时避免开方
+    fn distance_squared(&self, other: &RgbColor) -> u32 {
+        let channel = |a: u8, b: u8| (a.abs_diff(b) as u32).pow(2);
+        channel(self.r, other.r) + channel(self.g, other.g) + channel(self.b, other.b)
+    }
+
+    /// 在两个颜色之间线性插值
+    ///
+    /// `t` 会被限制在 [0, 1]，0 返回自身，1 返回 `other`，各通道四舍五入到 u8
+    pub fn lerp(&self, other: &RgbColor, t: f64) -> RgbColor {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+        RgbColor {
+            r: channel(self.r, other.r),
+            g: channel(self.g, other.g),
+            b: channel(self.b, other.b),
+        }
+    }
+
+    /// 生成从 `start` 到 `end` 的渐变色，共 `steps` 个颜色（包含首尾）
+    ///
+    /// `steps` 为 0 时返回空列表，为 1 时只返回 `start`
+    pub fn gradient(start: RgbColor, end: RgbColor, steps: usize) -> Vec<RgbColor> {
+        match steps {
+            0 => Vec::new(),
+            1 => vec![start],
+            _ => (0..steps)
+                .map(|i| start.lerp(&end, i as f64 / (steps - 1) as f64))
+                .collect(),
+        }
+    }
+}
+
+/// 颜色差异的计算方式
+///
+/// - `Manhattan`：各通道差值的绝对值之和（默认，与 `calculate_color_difference` 一致）
+/// - `Euclidean`：各通道差值平方和的平方根，同样的容差下对多通道的小偏差更宽松、对单通道的大偏差更严格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorMetric {
+    /// 曼哈顿距离（默认）
+    #[default]
+    Manhattan,
+    /// 欧几里得距离
+    Euclidean,
+}
+
+impl ColorMetric {
+    /// 两个颜色的差异是否在容差范围内（差异小于等于 `tolerance`）
+    pub fn within(self, a: &RgbColor, b: &RgbColor, tolerance: u32) -> bool {
+        match self {
+            ColorMetric::Manhattan => a.distance(b) <= tolerance,
+            ColorMetric::Euclidean => a.distance_squared(b) as u64 <= (tolerance as u64).pow(2),
+        }
+    }
+}
+
+/// 保存图像时的编码选项
+///
+/// 只有与文件格式对应的选项会生效，例如保存为 PNG 时忽略 `jpeg_quality`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SaveOptions {
+    /// JPEG 质量，范围 0-100，越大画质越好、文件越大；None 时使用 OpenCV 默认值（95）
+    pub jpeg_quality: Option<u8>,
+    /// PNG 压缩级别，范围 0-9，0 最快、9 文件最小；None 时使用 OpenCV 默认值（1）
+    pub png_compression: Option<u8>,
 }
 
 /// 图像数据（OpenCV Mat 的封装）
@@ -1,7 +1,9 @@
 use std::f64::consts::PI;
 
+use serde::{Serialize, Deserialize};
+
 /// 旋转方向枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RotationDirection {
     /// 左转
     Left,
@@ -17,6 +19,14 @@ impl RotationDirection {
             RotationDirection::Right => "右",
         }
     }
+
+    /// 转换为字符串（英文）
+    pub fn to_string_en(&self) -> &'static str {
+        match self {
+            RotationDirection::Left => "Left",
+            RotationDirection::Right => "Right",
+        }
+    }
 }
 
 /// 计算两点之间的欧几里得距离（直线距离）
@@ -67,8 +77,133 @@ pub fn calculate_distance_i32(x1: i32, y1: i32, x2: i32, y2: i32) -> f64 {
     (dx * dx + dy * dy).sqrt()
 }
 
+/// 计算两点之间距离的平方
+///
+/// 只需要比较距离大小（如查找最近点）时使用，省去开方；
+/// 与阈值比较时可以先把阈值平方一次
+///
+/// # 参数
+/// - `x1`: 第一个点的 X 坐标
+/// - `y1`: 第一个点的 Y 坐标
+/// - `x2`: 第二个点的 X 坐标
+/// - `y2`: 第二个点的 Y 坐标
+///
+/// # 返回
+/// 两点之间距离的平方
+///
+/// # 示例
+/// ```rust
+/// use math_utils::calculate_distance_squared;
+///
+/// let distance_squared = calculate_distance_squared(0.0, 0.0, 3.0, 4.0);
+/// assert_eq!(distance_squared, 25.0);
+/// ```
+pub fn calculate_distance_squared(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    let dx = x2 - x1;
+    let dy = y2 - y1;
+    dx * dx + dy * dy
+}
+
+/// 计算两点之间距离的平方（整数坐标版本）
+///
+/// # 参数
+/// - `x1`: 第一个点的 X 坐标
+/// - `y1`: 第一个点的 Y 坐标
+/// - `x2`: 第二个点的 X 坐标
+/// - `y2`: 第二个点的 Y 坐标
+///
+/// # 返回
+/// 两点之间距离的平方
+///
+/// # 示例
+/// ```rust
+/// use math_utils::calculate_distance_squared_i32;
+///
+/// let distance_squared = calculate_distance_squared_i32(0, 0, 3, 4);
+/// assert_eq!(distance_squared, 25.0);
+/// ```
+pub fn calculate_distance_squared_i32(x1: i32, y1: i32, x2: i32, y2: i32) -> f64 {
+    let dx = (x2 - x1) as f64;
+    let dy = (y2 - y1) as f64;
+    dx * dx + dy * dy
+}
+
+/// 判断两点之间的距离是否在容差范围内（例如"是否已到达目标点"）
+///
+/// 等价于 `calculate_distance(a, b) <= tolerance`，内部比较距离的平方，省去开方
+///
+/// # 参数
+/// - `a`: 点 1 (x, y)
+/// - `b`: 点 2 (x, y)
+/// - `tolerance`: 距离容差，负数时总是返回 false
+///
+/// # 示例
+/// ```rust
+/// use math_utils::within_tolerance;
+///
+/// assert!(within_tolerance((0.0, 0.0), (3.0, 4.0), 5.0));
+/// assert!(!within_tolerance((0.0, 0.0), (3.0, 4.0), 4.9));
+/// ```
+pub fn within_tolerance(a: (f64, f64), b: (f64, f64), tolerance: f64) -> bool {
+    if tolerance < 0.0 {
+        return false;
+    }
+
+    calculate_distance_squared(a.0, a.1, b.0, b.1) <= tolerance * tolerance
+}
+
+/// 判断两点之间的距离是否在容差范围内（整数坐标版本）
+///
+/// # 参数
+/// - `a`: 点 1 (x, y)
+/// - `b`: 点 2 (x, y)
+/// - `tolerance`: 距离容差，负数时总是返回 false
+pub fn within_tolerance_i32(a: (i32, i32), b: (i32, i32), tolerance: f64) -> bool {
+    within_tolerance((a.0 as f64, a.1 as f64), (b.0 as f64, b.1 as f64), tolerance)
+}
+
+/// 查找路径中距离当前坐标最近的点的索引
+///
+/// 与 `generate_new_path_array` 使用相同的查找规则（距离相同时取靠前的点），
+/// 返回索引而不是截取后的路径，便于关联与路径平行的其他数据（如每段的速度）
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `current`: 当前坐标 (x, y)
+///
+/// # 返回
+/// 最近点的索引；路径为空时返回 None
+///
+/// # 示例
+/// ```rust
+/// use math_utils::nearest_point_index;
+///
+/// let path = vec![(0.0, 0.0), (10.0, 10.0), (20.0, 20.0), (30.0, 30.0)];
+/// assert_eq!(nearest_point_index(&path, (18.0, 19.0)), Some(2));
+/// assert_eq!(nearest_point_index(&[], (0.0, 0.0)), None);
+/// ```
+pub fn nearest_point_index(path: &[(f64, f64)], current: (f64, f64)) -> Option<usize> {
+    let (current_x, current_y) = current;
+    let mut nearest = None;
+    let mut min_distance = f64::INFINITY;
+
+    // 遍历路径数组中的每个点，计算距离（比较大小只需距离的平方）
+    for (index, point) in path.iter().enumerate() {
+        let distance = calculate_distance_squared(current_x, current_y, point.0, point.1);
+        // 如果找到更短的距离，更新最短距离和索引
+        if nearest.is_none() || distance < min_distance {
+            min_distance = distance;
+            nearest = Some(index);
+        }
+    }
+
+    nearest
+}
+
 /// 根据当前坐标和路径数组，生成一个新的路径数组，从距离当前坐标最近的点开始
 ///
+/// 需要每帧对同一条路径重复查询时，使用 `PathTracker` 避免每次遍历整个数组
+///
 /// # 参数
 /// - `path_array`: 路径数组，包含多个坐标点
 /// - `current_coord`: 当前坐标 (x, y)
@@ -87,26 +222,244 @@ pub fn calculate_distance_i32(x1: i32, y1: i32, x2: i32, y2: i32) -> f64 {
 /// ```
 /// todo: 待优化，较python版慢
 pub fn generate_new_path_array(path_array: &[(f64, f64)], current_coord: (f64, f64)) -> Vec<(f64, f64)> {
-    if path_array.is_empty() {
-        return Vec::new();
+    match nearest_point_index(path_array, current_coord) {
+        // 从最短距离的点开始构建新的路径数组
+        Some(index) => path_array[index..].to_vec(),
+        None => Vec::new(),
     }
+}
 
-    let (current_x, current_y) = current_coord;
-    let mut min_distance = f64::INFINITY;
-    let mut min_distance_index = 0;
+/// 计算点到线段的最短距离
+///
+/// 将点投影到线段所在直线上，投影参数限制在 [0, 1]（即限制在线段两端点之间），
+/// 返回点到投影点的欧几里得距离。可用于判断当前位置是否偏离规划路线
+///
+/// # 参数
+/// - `px`, `py`: 点的坐标
+/// - `ax`, `ay`: 线段起点坐标
+/// - `bx`, `by`: 线段终点坐标
+///
+/// # 返回
+/// 点到线段的距离；线段长度为 0 时返回点到该端点的距离
+///
+/// # 示例
+/// ```rust
+/// use math_utils::distance_point_to_segment;
+///
+/// // 投影落在线段内，取垂直距离
+/// assert_eq!(distance_point_to_segment(5.0, 3.0, 0.0, 0.0, 10.0, 0.0), 3.0);
+/// // 投影落在线段外，取到最近端点的距离
+/// assert_eq!(distance_point_to_segment(13.0, 4.0, 0.0, 0.0, 10.0, 0.0), 5.0);
+/// ```
+pub fn distance_point_to_segment(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    let dx = bx - ax;
+    let dy = by - ay;
+    let length_squared = dx * dx + dy * dy;
 
-    // 遍历路径数组中的每个点，计算距离
-    for (index, point) in path_array.iter().enumerate() {
-        let distance = calculate_distance(current_x, current_y, point.0, point.1);
-        // 如果找到更短的距离，更新最短距离和索引
-        if distance < min_distance {
-            min_distance = distance;
-            min_distance_index = index;
+    if length_squared == 0.0 {
+        return calculate_distance(px, py, ax, ay);
+    }
+
+    let t = (((px - ax) * dx + (py - ay) * dy) / length_squared).clamp(0.0, 1.0);
+    calculate_distance(px, py, ax + t * dx, ay + t * dy)
+}
+
+/// `PathTracker` 默认的向前搜索窗口大小（点数）
+const DEFAULT_TRACKER_WINDOW: usize = 16;
+
+/// 沿固定路径行进时的最近点跟踪器
+///
+/// 每帧对同一条长路径调用 `generate_new_path_array` 需要遍历整个数组。
+/// `PathTracker` 记住上一次匹配到的索引，之后只在该索引向前 `window` 个点的范围内查找最近点，
+/// 沿路线单调前进时每次查询的开销与路径长度无关。
+/// 第一次查询（或调用 `reset` 之后）会遍历整条路径
+///
+/// # 示例
+/// ```rust
+/// use math_utils::PathTracker;
+///
+/// let path: Vec<(f64, f64)> = (0..100).map(|i| (i as f64 * 10.0, 0.0)).collect();
+/// let mut tracker = PathTracker::new(path);
+///
+/// assert_eq!(tracker.advance((42.0, 1.0))[0], (40.0, 0.0));
+/// assert_eq!(tracker.advance((68.0, 1.0))[0], (70.0, 0.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathTracker {
+    path: Vec<(f64, f64)>,
+    /// 上一次匹配到的最近点索引，None 表示下次查询需要完整遍历
+    last_index: Option<usize>,
+    window: usize,
+}
+
+impl PathTracker {
+    /// 使用默认搜索窗口创建跟踪器
+    pub fn new(path: Vec<(f64, f64)>) -> Self {
+        Self::with_window(path, DEFAULT_TRACKER_WINDOW)
+    }
+
+    /// 使用指定的向前搜索窗口创建跟踪器
+    ///
+    /// # 参数
+    /// - `path`: 路径数组
+    /// - `window`: 每次查询从上一次的最近点向前检查的点数，至少为 1
+    pub fn with_window(path: Vec<(f64, f64)>, window: usize) -> Self {
+        Self {
+            path,
+            last_index: None,
+            window: window.max(1),
         }
     }
 
-    // 从最短距离的点开始构建新的路径数组
-    path_array[min_distance_index..].to_vec()
+    /// 根据当前坐标更新最近点，返回从最近点开始的剩余路径
+    ///
+    /// 与 `generate_new_path_array` 的结果相同，前提是当前坐标沿路线前进，
+    /// 没有跳过超过 `window` 个点；发生大幅跳跃（如传送）后应先调用 `reset`
+    ///
+    /// # 参数
+    /// - `current_coord`: 当前坐标 (x, y)
+    ///
+    /// # 返回
+    /// 剩余路径；路径为空时返回空切片
+    pub fn advance(&mut self, current_coord: (f64, f64)) -> &[(f64, f64)] {
+        if self.path.is_empty() {
+            return &self.path;
+        }
+
+        let (start, end) = match self.last_index {
+            Some(index) => (index, (index + self.window + 1).min(self.path.len())),
+            None => (0, self.path.len()),
+        };
+
+        let min_distance_index =
+            start + nearest_point_index(&self.path[start..end], current_coord).unwrap_or(0);
+
+        self.last_index = Some(min_distance_index);
+        &self.path[min_distance_index..]
+    }
+
+    /// 上一次匹配到的最近点索引，尚未查询过时返回 None
+    pub fn current_index(&self) -> Option<usize> {
+        self.last_index
+    }
+
+    /// 完整路径
+    pub fn path(&self) -> &[(f64, f64)] {
+        &self.path
+    }
+
+    /// 清除记录的索引，下一次 `advance` 将重新遍历整条路径
+    pub fn reset(&mut self) {
+        self.last_index = None;
+    }
+}
+
+/// 根据当前坐标和闭环路径，生成一个从最近点开始、绕行一整圈的路径数组
+///
+/// 适用于循环巡逻路线：从距离当前坐标最近的点开始，走到路径末尾后回到开头，
+/// 一直到起点的前一个点为止。首尾两点相同（显式闭合的路径）时，末尾的重复点会被忽略
+///
+/// # 参数
+/// - `path`: 闭环路径数组
+/// - `current`: 当前坐标 (x, y)
+///
+/// # 返回
+/// 绕行一整圈的路径数组；空路径返回空数组，单点路径原样返回
+///
+/// # 示例
+/// ```rust
+/// use math_utils::generate_loop_path_array;
+///
+/// let path = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+/// let new_path = generate_loop_path_array(&path, (11.0, 9.0));
+/// assert_eq!(new_path, vec![(10.0, 10.0), (0.0, 10.0), (0.0, 0.0), (10.0, 0.0)]);
+/// ```
+pub fn generate_loop_path_array(path: &[(f64, f64)], current: (f64, f64)) -> Vec<(f64, f64)> {
+    // 显式闭合的路径去掉末尾与起点重复的点
+    let path = match path {
+        [first, .., last] if first == last => &path[..path.len() - 1],
+        _ => path,
+    };
+
+    if path.len() <= 1 {
+        return path.to_vec();
+    }
+
+    let min_distance_index = nearest_point_index(path, current).unwrap_or(0);
+
+    // 从最近点走到末尾，再从开头走到最近点之前
+    path[min_distance_index..]
+        .iter()
+        .chain(&path[..min_distance_index])
+        .copied()
+        .collect()
+}
+
+/// 根据当前坐标和路径数组，生成一个新的路径数组，并可选地去除重复点
+///
+/// 与 `generate_new_path_array` 相同，`dedup_epsilon` 为 Some 时对结果调用 `dedup_path`，
+/// 去掉与前一个保留点距离不超过 epsilon 的点，避免沿路径行进时在重复点上浪费步数
+///
+/// # 参数
+/// - `path_array`: 路径数组，包含多个坐标点
+/// - `current_coord`: 当前坐标 (x, y)
+/// - `dedup_epsilon`: 去重距离，None 时不去重
+///
+/// # 返回
+/// 从距离当前坐标最近的点开始的路径数组
+///
+/// # 示例
+/// ```rust
+/// use math_utils::generate_new_path_array_with_dedup;
+///
+/// let path = vec![(0.0, 0.0), (10.0, 10.0), (10.0, 10.0), (20.0, 20.0)];
+/// let new_path = generate_new_path_array_with_dedup(&path, (9.0, 9.0), Some(0.5));
+/// assert_eq!(new_path, vec![(10.0, 10.0), (20.0, 20.0)]);
+/// ```
+pub fn generate_new_path_array_with_dedup(
+    path_array: &[(f64, f64)],
+    current_coord: (f64, f64),
+    dedup_epsilon: Option<f64>,
+) -> Vec<(f64, f64)> {
+    let new_path = generate_new_path_array(path_array, current_coord);
+    match dedup_epsilon {
+        Some(epsilon) => dedup_path(&new_path, epsilon),
+        None => new_path,
+    }
+}
+
+/// 去除路径中的重复点和近似重复点
+///
+/// 依次检查每个点，与上一个保留的点距离不超过 `epsilon` 时丢弃，
+/// 适用于采样得到的轨迹数据中常见的连续重复点
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `epsilon`: 去重距离，为 0 时只去除完全相同的点
+///
+/// # 返回
+/// 去重后的路径，第一个点总是保留
+///
+/// # 示例
+/// ```rust
+/// use math_utils::dedup_path;
+///
+/// let path = vec![(0.0, 0.0), (0.0, 0.0), (0.1, 0.0), (5.0, 0.0)];
+/// assert_eq!(dedup_path(&path, 0.5), vec![(0.0, 0.0), (5.0, 0.0)]);
+/// ```
+pub fn dedup_path(path: &[(f64, f64)], epsilon: f64) -> Vec<(f64, f64)> {
+    let mut result: Vec<(f64, f64)> = Vec::with_capacity(path.len());
+
+    for &point in path {
+        let is_duplicate = result.last().is_some_and(|last| {
+            calculate_distance(last.0, last.1, point.0, point.1) <= epsilon
+        });
+        if !is_duplicate {
+            result.push(point);
+        }
+    }
+
+    result
 }
 
 /// 根据当前坐标和路径数组，生成一个新的路径数组，从距离当前坐标最近的点开始（整数坐标版本）
@@ -136,9 +489,9 @@ pub fn generate_new_path_array_i32(path_array: &[(i32, i32)], current_coord: (i3
     let mut min_distance = f64::INFINITY;
     let mut min_distance_index = 0;
 
-    // 遍历路径数组中的每个点，计算距离
+    // 遍历路径数组中的每个点，计算距离（比较大小只需距离的平方）
     for (index, point) in path_array.iter().enumerate() {
-        let distance = calculate_distance_i32(current_x, current_y, point.0, point.1);
+        let distance = calculate_distance_squared_i32(current_x, current_y, point.0, point.1);
         // 如果找到更短的距离，更新最短距离和索引
         if distance < min_distance {
             min_distance = distance;
@@ -150,10 +503,45 @@ pub fn generate_new_path_array_i32(path_array: &[(i32, i32)], current_coord: (i3
     path_array[min_distance_index..].to_vec()
 }
 
+/// 计算从点 1 指向点 2 的方位角
+///
+/// 坐标约定为屏幕坐标系（y 轴向下）：0 度指向屏幕上方（y 减小的方向），
+/// 顺时针递增，90 度指向右（x 增大），180 度指向下，270 度指向左。
+/// 与 `calculate_angle_old` 及 `calculate_rotation_angle_old` 使用的约定相同
+///
+/// # 参数
+/// - `x1`: 第一个点的 X 坐标
+/// - `y1`: 第一个点的 Y 坐标
+/// - `x2`: 第二个点的 X 坐标
+/// - `y2`: 第二个点的 Y 坐标
+///
+/// # 返回
+/// 方位角，范围 [0, 360)；两点重合时返回 None
+///
+/// # 示例
+/// ```rust
+/// use math_utils::calculate_angle;
+///
+/// assert_eq!(calculate_angle(0.0, 0.0, 0.0, -1.0), Some(0.0));
+/// assert_eq!(calculate_angle(0.0, 0.0, 1.0, 0.0), Some(90.0));
+/// assert_eq!(calculate_angle(1.0, 1.0, 1.0, 1.0), None);
+/// ```
+pub fn calculate_angle(x1: f64, y1: f64, x2: f64, y2: f64) -> Option<f64> {
+    let dx = x2 - x1;
+    let dy = y2 - y1;
+
+    if dx == 0.0 && dy == 0.0 {
+        return None;
+    }
+
+    // y 轴向下，因此以 -dy 作为"向上"分量
+    Some(dx.atan2(-dy).to_degrees().rem_euclid(360.0))
+}
+
 /// 计算_求斜率旧未修改版（根据两点运算角度）
 ///
 /// 根据两点坐标计算角度，返回 0-360 度的角度值
-/// 角度以 y 轴正方向为 0 度，逆时针递增
+/// 坐标约定为屏幕坐标系（y 轴向下）：0 度指向屏幕上方（y 减小的方向），顺时针递增，与 `calculate_angle` 相同
 ///
 /// # 参数
 /// - `x1`: 第一个点的 X 坐标
@@ -164,6 +552,10 @@ pub fn generate_new_path_array_i32(path_array: &[(i32, i32)], current_coord: (i3
 /// # 返回
 /// 角度值（0-360 度），如果两点相同或无法计算则返回 NaN
 ///
+/// # 注意
+/// 保留用于兼容旧代码，新代码推荐使用 `calculate_angle`：
+/// 两者对不重合的两点结果一致，但 `calculate_angle` 基于 `atan2` 实现，两点重合时返回 None 而不是 NaN
+///
 /// # 示例
 /// ```rust
 /// use math_utils::calculate_angle_old;
@@ -219,7 +611,7 @@ pub fn calculate_angle_old(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
 /// 根据当前朝向角度和朝向坐标、目的地坐标，计算需要转动的角度和方向
 ///
 /// # 参数
-/// - `current_angle`: 当前朝向角度（0-360 度）
+/// - `current_angle`: 当前朝向角度（度），可以超出 0-360
 /// - `current_x`: 当前朝向坐标 X
 /// - `current_y`: 当前朝向坐标 Y
 /// - `target_x`: 目的地坐标 X
@@ -245,56 +637,765 @@ pub fn calculate_rotation_angle_old(
     target_x: f64,
     target_y: f64,
 ) -> (RotationDirection, f64) {
+    let (direction, angle, _) =
+        calculate_rotation_angle_with_heading(current_angle, current_x, current_y, target_x, target_y);
+    (direction, angle)
+}
+
+/// 计算需要转动的角度和方向，并返回转动后的朝向
+///
+/// 与 `calculate_rotation_angle_old` 相同，额外返回转动完成后的朝向角度（即目标方位角），
+/// 便于链式计算下一步，而不必再调用一次 `calculate_angle_old`
+///
+/// # 参数
+/// - `current_angle`: 当前朝向角度（度），可以超出 0-360
+/// - `current_x`: 当前朝向坐标 X
+/// - `current_y`: 当前朝向坐标 Y
+/// - `target_x`: 目的地坐标 X
+/// - `target_y`: 目的地坐标 Y
+///
+/// # 返回
+/// 元组 (方向, 角度, 新朝向)，其中新朝向范围为 [0, 360)；
+/// 如果两点相同无法计算目标角度，新朝向为标准化后的当前朝向
+///
+/// # 示例
+/// ```rust
+/// use math_utils::{calculate_rotation_angle_with_heading, RotationDirection};
+///
+/// let (direction, angle, heading) = calculate_rotation_angle_with_heading(0.0, 0.0, 0.0, 1.0, 0.0);
+/// assert_eq!(direction, RotationDirection::Right);
+/// assert!((angle - 90.0).abs() < 1e-10);
+/// assert!((heading - 90.0).abs() < 1e-10);
+/// ```
+pub fn calculate_rotation_angle_with_heading(
+    current_angle: f64,
+    current_x: f64,
+    current_y: f64,
+    target_x: f64,
+    target_y: f64,
+) -> (RotationDirection, f64, f64) {
     // 计算从朝向坐标到目的地的角度
     let target_angle = calculate_angle_old(current_x, current_y, target_x, target_y);
     
-    // 如果角度计算失败（NaN），返回默认值
+    // 如果角度计算失败（NaN），返回默认值，朝向保持不变
     if target_angle.is_nan() {
-        return (RotationDirection::Right, 0.0);
-    }
-    
-    // 计算角度差
-    let mut angle_diff = target_angle - current_angle;
-    
-    // 标准化角度差到 [-360, 360] 范围
-    while angle_diff > 360.0 {
-        angle_diff -= 360.0;
-    }
-    while angle_diff < -360.0 {
-        angle_diff += 360.0;
+        return (RotationDirection::Right, 0.0, current_angle.rem_euclid(360.0));
     }
     
-    // 根据角度差判断方向和计算转动角度
+    // 角度差标准化到 (-180, 180]，正值为右转，负值为左转
+    let angle_diff = normalize_angle(target_angle - current_angle);
+
     if angle_diff > 0.0 && angle_diff < 180.0 {
-        // 角度差在 (0, 180) 之间，向右转
-        (RotationDirection::Right, angle_diff)
-    } else if angle_diff >= -180.0 && angle_diff < 0.0 {
-        // 角度差在 [-180, 0) 之间，向左转
-        (RotationDirection::Left, angle_diff.abs())
-    } else if angle_diff >= 180.0 && angle_diff < 360.0 {
-        // 角度差在 [180, 360) 之间，向左转（更短路径）
-        (RotationDirection::Left, 360.0 - angle_diff)
-    } else if angle_diff >= -360.0 && angle_diff < -180.0 {
-        // 角度差在 [-360, -180) 之间，向右转（更短路径）
-        (RotationDirection::Right, 360.0 + angle_diff)
+        (RotationDirection::Right, angle_diff, target_angle)
+    } else if angle_diff < 0.0 || angle_diff == 180.0 {
+        // 正好相差 180 度时两个方向距离相同，沿用向左转
+        (RotationDirection::Left, angle_diff.abs(), target_angle)
     } else {
-        // 其他情况（包括 angle_diff == 0），默认向右转，角度为 0
-        (RotationDirection::Right, 0.0)
+        // 角度差为 0，默认向右转，角度为 0
+        (RotationDirection::Right, 0.0, target_angle)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// 计算需要转动的角度和方向（带死区）
+///
+/// 与 `calculate_rotation_angle_old` 相同，但当需要转动的角度小于 `deadzone_deg` 时
+/// 视为已经对准，返回 `(RotationDirection::Right, 0.0)`（即直行），
+/// 避免在已大致对准时因微小角度反复调整而来回抖动
+///
+/// # 参数
+/// - `current_angle`: 当前朝向角度（度），可以超出 0-360
+/// - `current_x`: 当前朝向坐标 X
+/// - `current_y`: 当前朝向坐标 Y
+/// - `target_x`: 目的地坐标 X
+/// - `target_y`: 目的地坐标 Y
+/// - `deadzone_deg`: 死区角度（度），小于该值的转动将被忽略
+///
+/// # 返回
+/// 元组 (方向, 角度)，在死区内时为 `(RotationDirection::Right, 0.0)`
+///
+/// # 示例
+/// ```rust
+/// use math_utils::{calculate_rotation_angle_with_deadzone, RotationDirection};
+///
+/// // 目标角度 90 度，当前朝向 89.5 度，在 1 度死区内，不需要转动
+/// let (direction, angle) = calculate_rotation_angle_with_deadzone(89.5, 0.0, 0.0, 1.0, 0.0, 1.0);
+/// assert_eq!(direction, RotationDirection::Right);
+/// assert_eq!(angle, 0.0);
+/// ```
+pub fn calculate_rotation_angle_with_deadzone(
+    current_angle: f64,
+    current_x: f64,
+    current_y: f64,
+    target_x: f64,
+    target_y: f64,
+    deadzone_deg: f64,
+) -> (RotationDirection, f64) {
+    let (direction, angle) =
+        calculate_rotation_angle_old(current_angle, current_x, current_y, target_x, target_y);
 
-    #[test]
-    fn test_calculate_distance() {
-        // 测试 3-4-5 直角三角形
-        let distance = calculate_distance(0.0, 0.0, 3.0, 4.0);
-        assert!((distance - 5.0).abs() < 1e-10);
-        
-        // 测试相同点
-        let distance = calculate_distance(5.0, 5.0, 5.0, 5.0);
+    if angle < deadzone_deg {
+        (RotationDirection::Right, 0.0)
+    } else {
+        (direction, angle)
+    }
+}
+
+/// 将角度标准化到 (-180, 180] 范围
+///
+/// 适用于角度差：结果的符号即转动方向（正值为右转，负值为左转），绝对值即最短转动角度。
+/// 输入可以是任意大小的角度（如 725 度或 -400 度）
+///
+/// # 参数
+/// - `deg`: 角度（度）
+///
+/// # 返回
+/// 标准化后的角度，范围 (-180, 180]
+///
+/// # 示例
+/// ```rust
+/// use math_utils::normalize_angle;
+///
+/// assert!((normalize_angle(725.0) - 5.0).abs() < 1e-10);
+/// assert!((normalize_angle(270.0) - -90.0).abs() < 1e-10);
+/// assert!((normalize_angle(-180.0) - 180.0).abs() < 1e-10);
+/// ```
+pub fn normalize_angle(deg: f64) -> f64 {
+    let angle = deg.rem_euclid(360.0);
+    if angle > 180.0 {
+        angle - 360.0
+    } else {
+        angle
+    }
+}
+
+/// 计算从方位角 `from` 转到 `to` 的有符号最短角度差
+///
+/// 正值表示右转（顺时针，方位角增大），负值表示左转（逆时针），
+/// 与 `calculate_rotation_angle_old` 返回的 `RotationDirection` 含义一致；
+/// 正好相差 180 度时返回 180
+///
+/// # 参数
+/// - `from_deg`: 起始方位角（度），可以超出 0-360
+/// - `to_deg`: 目标方位角（度），可以超出 0-360
+///
+/// # 返回
+/// 有符号角度差，范围 (-180, 180]
+///
+/// # 示例
+/// ```rust
+/// use math_utils::shortest_angle_diff;
+///
+/// // 从 350 度经过 0 度右转到 10 度
+/// assert!((shortest_angle_diff(350.0, 10.0) - 20.0).abs() < 1e-10);
+/// // 从 10 度左转到 350 度
+/// assert!((shortest_angle_diff(10.0, 350.0) - -20.0).abs() < 1e-10);
+/// ```
+pub fn shortest_angle_diff(from_deg: f64, to_deg: f64) -> f64 {
+    normalize_angle(to_deg - from_deg)
+}
+
+/// 计算两个方位角之间的最短角距离（不区分方向）
+///
+/// 即 `calculate_rotation_angle_old` 返回的转动角度大小部分，单独提供，
+/// 便于做"朝向是否在容差范围内"之类的判断
+///
+/// # 参数
+/// - `a`: 方位角 1（度），可以超出 0-360
+/// - `b`: 方位角 2（度），可以超出 0-360
+///
+/// # 返回
+/// 最短角距离，范围 [0, 180]
+///
+/// # 示例
+/// ```rust
+/// use math_utils::angular_distance;
+///
+/// assert!((angular_distance(350.0, 10.0) - 20.0).abs() < 1e-10);
+/// assert!((angular_distance(0.0, 180.0) - 180.0).abs() < 1e-10);
+/// ```
+pub fn angular_distance(a: f64, b: f64) -> f64 {
+    let diff = (b - a).rem_euclid(360.0);
+    if diff > 180.0 {
+        360.0 - diff
+    } else {
+        diff
+    }
+}
+
+/// 沿最短弧在两个方位角之间插值
+///
+/// 例如从 350 度到 10 度会经过 0 度（共 20 度），而不是反向绕行 340 度；
+/// 两者正好相差 180 度时按右转（顺时针）方向插值
+///
+/// # 参数
+/// - `a`: 起始方位角（度）
+/// - `b`: 目标方位角（度）
+/// - `t`: 插值系数，0 返回 `a`，1 返回 `b`
+///
+/// # 返回
+/// 插值后的方位角，范围 [0, 360)
+///
+/// # 示例
+/// ```rust
+/// use math_utils::lerp_angle;
+///
+/// assert!((lerp_angle(350.0, 10.0, 0.5) - 0.0).abs() < 1e-10);
+/// assert!((lerp_angle(350.0, 10.0, 0.75) - 5.0).abs() < 1e-10);
+/// ```
+pub fn lerp_angle(a: f64, b: f64, t: f64) -> f64 {
+    (a + shortest_angle_diff(a, b) * t).rem_euclid(360.0)
+}
+
+/// 将点绕指定中心点旋转
+///
+/// 角度约定与 `calculate_angle_old` 一致（屏幕坐标系，y 轴向下）：
+/// 正角度为顺时针旋转，即旋转后中心点指向该点的方位角增加 `angle_deg`，
+/// 与 `RotationDirection::Right` 的转动方向相同
+///
+/// # 参数
+/// - `px`, `py`: 要旋转的点
+/// - `cx`, `cy`: 旋转中心
+/// - `angle_deg`: 旋转角度（度），正数顺时针，负数逆时针
+///
+/// # 返回
+/// 旋转后的点 (x, y)
+///
+/// # 示例
+/// ```rust
+/// use math_utils::rotate_point;
+///
+/// // 中心正上方的点顺时针旋转 90 度，变为中心正右方
+/// let (x, y) = rotate_point(10.0, 0.0, 10.0, 10.0, 90.0);
+/// assert!((x - 20.0).abs() < 1e-10);
+/// assert!((y - 10.0).abs() < 1e-10);
+/// ```
+pub fn rotate_point(px: f64, py: f64, cx: f64, cy: f64, angle_deg: f64) -> (f64, f64) {
+    let (sin, cos) = angle_deg.to_radians().sin_cos();
+    rotate_point_sin_cos(px, py, cx, cy, sin, cos)
+}
+
+/// 使用预先计算好的正弦、余弦旋转点，批量旋转时避免重复计算三角函数
+fn rotate_point_sin_cos(px: f64, py: f64, cx: f64, cy: f64, sin: f64, cos: f64) -> (f64, f64) {
+    let dx = px - cx;
+    let dy = py - cy;
+    (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+}
+
+/// 将路径绕指定中心点旋转
+///
+/// 对每个点调用 `rotate_point`。角度约定与 `calculate_angle_old` 一致（屏幕坐标系，y 轴向下）：
+/// 正角度为顺时针旋转，即旋转后每段的朝向角度增加 `angle_deg`
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `pivot`: 旋转中心 (x, y)
+/// - `angle_deg`: 旋转角度（度），正数顺时针，负数逆时针
+///
+/// # 返回
+/// 旋转后的新路径
+///
+/// # 示例
+/// ```rust
+/// use math_utils::rotate_path;
+///
+/// // 正上方的点绕原点顺时针旋转 90 度，变为正右方
+/// let rotated = rotate_path(&[(0.0, -10.0)], (0.0, 0.0), 90.0);
+/// assert!((rotated[0].0 - 10.0).abs() < 1e-10);
+/// assert!(rotated[0].1.abs() < 1e-10);
+/// ```
+pub fn rotate_path(path: &[(f64, f64)], pivot: (f64, f64), angle_deg: f64) -> Vec<(f64, f64)> {
+    let (sin, cos) = angle_deg.to_radians().sin_cos();
+    let (cx, cy) = pivot;
+
+    path.iter()
+        .map(|&(x, y)| rotate_point_sin_cos(x, y, cx, cy, sin, cos))
+        .collect()
+}
+
+/// 平移路径
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `offset`: 平移量 (dx, dy)
+///
+/// # 返回
+/// 平移后的新路径
+///
+/// # 示例
+/// ```rust
+/// use math_utils::translate_path;
+///
+/// let moved = translate_path(&[(0.0, 0.0), (10.0, 10.0)], (5.0, -5.0));
+/// assert_eq!(moved, vec![(5.0, -5.0), (15.0, 5.0)]);
+/// ```
+pub fn translate_path(path: &[(f64, f64)], offset: (f64, f64)) -> Vec<(f64, f64)> {
+    path.iter()
+        .map(|&(x, y)| (x + offset.0, y + offset.1))
+        .collect()
+}
+
+/// 以指定中心点缩放路径
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `pivot`: 缩放中心 (x, y)，该点位置保持不变
+/// - `factor`: 缩放倍数
+///
+/// # 返回
+/// 缩放后的新路径
+///
+/// # 示例
+/// ```rust
+/// use math_utils::scale_path;
+///
+/// let scaled = scale_path(&[(10.0, 10.0), (20.0, 10.0)], (10.0, 10.0), 2.0);
+/// assert_eq!(scaled, vec![(10.0, 10.0), (30.0, 10.0)]);
+/// ```
+pub fn scale_path(path: &[(f64, f64)], pivot: (f64, f64), factor: f64) -> Vec<(f64, f64)> {
+    let (cx, cy) = pivot;
+
+    path.iter()
+        .map(|&(x, y)| (cx + (x - cx) * factor, cy + (y - cy) * factor))
+        .collect()
+}
+
+/// 计算路径整体的转向（左弯还是右弯）
+///
+/// 累加路径上相邻线段之间的有符号朝向变化（每次取最短转向），
+/// 正值表示右转（顺时针），负值表示左转，用于对路径的机动类型做分类
+///
+/// # 参数
+/// - `path`: 路径数组
+///
+/// # 返回
+/// 元组 (方向, 总转动角度)，总转动角度为累加结果的绝对值（度）；
+/// 有效线段少于 2 条时返回 `(RotationDirection::Right, 0.0)`
+///
+/// # 示例
+/// ```rust
+/// use math_utils::{net_rotation, RotationDirection};
+///
+/// // 向北 -> 向东 -> 向南，共右转 180 度
+/// let path = vec![(0.0, 10.0), (0.0, 0.0), (10.0, 0.0), (10.0, 10.0)];
+/// let (direction, angle) = net_rotation(&path);
+/// assert_eq!(direction, RotationDirection::Right);
+/// assert!((angle - 180.0).abs() < 1e-10);
+/// ```
+pub fn net_rotation(path: &[(f64, f64)]) -> (RotationDirection, f64) {
+    let headings = segment_headings(path);
+
+    let total: f64 = headings
+        .windows(2)
+        .map(|pair| shortest_angle_diff(pair[0], pair[1]))
+        .sum();
+
+    if total < 0.0 {
+        (RotationDirection::Left, -total)
+    } else {
+        (RotationDirection::Right, total)
+    }
+}
+
+/// 计算路径总长度（相邻点之间距离之和）
+///
+/// # 参数
+/// - `path`: 路径数组
+///
+/// # 返回
+/// 路径总长度；少于 2 个点时返回 0
+///
+/// # 示例
+/// ```rust
+/// use math_utils::path_length;
+///
+/// let path = vec![(0.0, 0.0), (3.0, 4.0), (3.0, 10.0)];
+/// assert_eq!(path_length(&path), 11.0);
+/// ```
+pub fn path_length(path: &[(f64, f64)]) -> f64 {
+    path.windows(2)
+        .map(|segment| calculate_distance(segment[0].0, segment[0].1, segment[1].0, segment[1].1))
+        .sum()
+}
+
+/// 计算从起点沿路径到每个点的累计距离，用于估算行进进度
+///
+/// # 参数
+/// - `path`: 路径数组
+///
+/// # 返回
+/// 与 `path` 等长的数组，第 i 个元素为起点到第 i 个点的路径距离（第一个元素为 0）；
+/// 空路径返回空数组
+///
+/// # 示例
+/// ```rust
+/// use math_utils::cumulative_distances;
+///
+/// let path = vec![(0.0, 0.0), (3.0, 4.0), (3.0, 10.0)];
+/// assert_eq!(cumulative_distances(&path), vec![0.0, 5.0, 11.0]);
+/// ```
+pub fn cumulative_distances(path: &[(f64, f64)]) -> Vec<f64> {
+    let mut distances = Vec::with_capacity(path.len());
+    let mut total = 0.0;
+
+    for (index, point) in path.iter().enumerate() {
+        if index > 0 {
+            let previous = path[index - 1];
+            total += calculate_distance(previous.0, previous.1, point.0, point.1);
+        }
+        distances.push(total);
+    }
+
+    distances
+}
+
+/// 按固定间距重新采样路径
+///
+/// 沿折线行进，每隔 `spacing` 在原始顶点之间线性插值出一个点，使点间距均匀，
+/// 便于平滑移动。结果总是包含起点和终点，最后一段的间距可能小于 `spacing`
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `spacing`: 采样间距，不大于 0 或为 NaN 时原样返回路径
+///
+/// # 返回
+/// 重新采样后的路径；`spacing` 大于路径总长度时只返回起点和终点
+///
+/// # 示例
+/// ```rust
+/// use math_utils::resample_path;
+///
+/// let path = vec![(0.0, 0.0), (10.0, 0.0)];
+/// assert_eq!(
+///     resample_path(&path, 3.0),
+///     vec![(0.0, 0.0), (3.0, 0.0), (6.0, 0.0), (9.0, 0.0), (10.0, 0.0)]
+/// );
+/// ```
+pub fn resample_path(path: &[(f64, f64)], spacing: f64) -> Vec<(f64, f64)> {
+    if path.len() <= 1 || spacing.is_nan() || spacing <= 0.0 {
+        return path.to_vec();
+    }
+
+    let mut result = vec![path[0]];
+    // 下一个采样点距起点的路径距离
+    let mut next_distance = spacing;
+    let mut travelled = 0.0;
+
+    for segment in path.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let length = calculate_distance(a.0, a.1, b.0, b.1);
+
+        while length > 0.0 && next_distance <= travelled + length {
+            let t = (next_distance - travelled) / length;
+            result.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+            next_distance += spacing;
+        }
+        travelled += length;
+    }
+
+    // 最后一个采样点与终点重合（总长度正好是间距的整数倍）时用终点替换，避免重复
+    let last_sample_distance = next_distance - spacing;
+    if result.len() > 1 && travelled - last_sample_distance <= spacing * 1e-9 {
+        result.pop();
+    }
+    result.push(path[path.len() - 1]);
+
+    result
+}
+
+/// 计算路径的外接矩形（坐标轴对齐）
+///
+/// # 参数
+/// - `path`: 路径数组
+///
+/// # 返回
+/// (min_x, min_y, max_x, max_y)；路径为空时返回 None
+///
+/// # 示例
+/// ```rust
+/// use math_utils::path_bounds;
+///
+/// let path = vec![(10.0, 5.0), (-2.0, 8.0), (4.0, -3.0)];
+/// assert_eq!(path_bounds(&path), Some((-2.0, -3.0, 10.0, 8.0)));
+/// ```
+pub fn path_bounds(path: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)> {
+    let (&(x0, y0), rest) = path.split_first()?;
+
+    Some(rest.iter().fold((x0, y0, x0, y0), |(min_x, min_y, max_x, max_y), &(x, y)| {
+        (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+    }))
+}
+
+/// 计算路径的外接矩形（整数坐标版本）
+///
+/// # 参数
+/// - `path`: 路径数组（整数坐标）
+///
+/// # 返回
+/// (min_x, min_y, max_x, max_y)；路径为空时返回 None
+pub fn path_bounds_i32(path: &[(i32, i32)]) -> Option<(i32, i32, i32, i32)> {
+    let (&(x0, y0), rest) = path.split_first()?;
+
+    Some(rest.iter().fold((x0, y0, x0, y0), |(min_x, min_y, max_x, max_y), &(x, y)| {
+        (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+    }))
+}
+
+/// 计算路径的转向加权代价
+///
+/// 代价 = 各线段长度之和 + `turn_penalty` × 每个拐角的转向角度（弧度，取绝对值）之和。
+/// 长度相近时更偏向转弯少、更直的路线，用于在多条候选路线之间做选择
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `turn_penalty`: 每弧度转向的代价，为 0 时等价于路径长度
+///
+/// # 返回
+/// 路径代价；少于 2 个点时返回 0
+///
+/// # 示例
+/// ```rust
+/// use math_utils::path_cost;
+///
+/// // 直线 20 与折线 20 长度相同，但折线有一个 90 度拐角
+/// let straight = vec![(0.0, 0.0), (20.0, 0.0)];
+/// let bent = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)];
+/// assert!(path_cost(&straight, 5.0) < path_cost(&bent, 5.0));
+/// ```
+pub fn path_cost(path: &[(f64, f64)], turn_penalty: f64) -> f64 {
+    let length = path_length(path);
+
+    let turning: f64 = segment_headings(path)
+        .windows(2)
+        .map(|pair| shortest_angle_diff(pair[0], pair[1]).abs().to_radians())
+        .sum();
+
+    length + turn_penalty * turning
+}
+
+/// 计算对准路径上即将行进的线段所需的转向
+///
+/// 找到离当前位置最近的线段（按点到线段的垂直距离），取该线段的朝向，
+/// 再按 `calculate_rotation_angle_old` 的规则计算从当前朝向转过去的方向和角度
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `current_pos`: 当前位置 (x, y)
+/// - `current_angle`: 当前朝向角度（度），可以超出 0-360
+///
+/// # 返回
+/// 元组 (方向, 角度)；路径少于 2 个点或所有点重合时返回 None
+///
+/// # 示例
+/// ```rust
+/// use math_utils::{turn_toward_path, RotationDirection};
+///
+/// // 路径向东延伸，当前朝北，需要右转 90 度
+/// let path = vec![(0.0, 0.0), (100.0, 0.0)];
+/// let (direction, angle) = turn_toward_path(&path, (50.0, 3.0), 0.0).unwrap();
+/// assert_eq!(direction, RotationDirection::Right);
+/// assert!((angle - 90.0).abs() < 1e-10);
+/// ```
+pub fn turn_toward_path(
+    path: &[(f64, f64)],
+    current_pos: (f64, f64),
+    current_angle: f64,
+) -> Option<(RotationDirection, f64)> {
+    let (px, py) = current_pos;
+
+    let (start, end) = path
+        .windows(2)
+        .filter(|segment| segment[0] != segment[1])
+        .map(|segment| {
+            let (a, b) = (segment[0], segment[1]);
+            (a, b, distance_point_to_segment(px, py, a.0, a.1, b.0, b.1))
+        })
+        .min_by(|x, y| x.2.partial_cmp(&y.2).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(a, b, _)| (a, b))?;
+
+    Some(calculate_rotation_angle_old(current_angle, start.0, start.1, end.0, end.1))
+}
+
+/// 计算路径每条线段的朝向角度（跳过长度为 0 的线段）
+fn segment_headings(path: &[(f64, f64)]) -> Vec<f64> {
+    path.windows(2)
+        .map(|segment| calculate_angle_old(segment[0].0, segment[0].1, segment[1].0, segment[1].1))
+        .filter(|angle| !angle.is_nan())
+        .collect()
+}
+
+/// 斜接长度上限（相对于偏移距离的倍数），超过时按上限截断，避免尖角处的点飞得过远
+const MITER_LIMIT: f64 = 4.0;
+
+/// 计算路径的平行偏移路径（路径膨胀）
+///
+/// 将每个顶点沿其局部方向的垂直方向偏移 `offset`，拐角处使用斜接（miter），
+/// 角度过尖导致斜接长度超过 `offset` 的 4 倍时截断为 4 倍，避免出现极远的尖刺；
+/// 180 度折返的拐角退化为沿前一段的法线偏移。重复点会被跳过，沿用相邻线段的方向
+///
+/// 方向约定与 `calculate_angle_old` 一致（屏幕坐标系，y 轴向下）：
+/// `offset` 为正时偏向行进方向的右侧，为负时偏向左侧
+///
+/// # 参数
+/// - `path`: 路径数组
+/// - `offset`: 偏移距离，正数向右，负数向左
+///
+/// # 返回
+/// 与原路径点数相同的偏移路径；少于 2 个点或所有点重合时原样返回
+///
+/// # 示例
+/// ```rust
+/// use math_utils::offset_path;
+///
+/// // 向右（东）行进，右侧是 y 增大的方向
+/// let offset = offset_path(&[(0.0, 0.0), (10.0, 0.0)], 2.0);
+/// assert_eq!(offset, vec![(0.0, 2.0), (10.0, 2.0)]);
+/// ```
+pub fn offset_path(path: &[(f64, f64)], offset: f64) -> Vec<(f64, f64)> {
+    if path.len() < 2 {
+        return path.to_vec();
+    }
+
+    // 每条线段的右侧单位法线，长度为 0 的线段为 None
+    let normals: Vec<Option<(f64, f64)>> = path
+        .windows(2)
+        .map(|segment| {
+            let (dx, dy) = (segment[1].0 - segment[0].0, segment[1].1 - segment[0].1);
+            let length = (dx * dx + dy * dy).sqrt();
+            if length == 0.0 {
+                None
+            } else {
+                Some((-dy / length, dx / length))
+            }
+        })
+        .collect();
+
+    if normals.iter().all(Option::is_none) {
+        return path.to_vec();
+    }
+
+    path.iter()
+        .enumerate()
+        .map(|(index, &(x, y))| {
+            // 顶点前后最近的有效线段法线
+            let prev = normals[..index.min(normals.len())].iter().rev().find_map(|n| *n);
+            let next = normals[index.min(normals.len())..].iter().find_map(|n| *n);
+
+            let (nx, ny) = match (prev, next) {
+                (Some(n1), Some(n2)) => {
+                    let (sx, sy) = (n1.0 + n2.0, n1.1 + n2.1);
+                    let sum_length = (sx * sx + sy * sy).sqrt();
+                    if sum_length < 1e-12 {
+                        // 180 度折返，没有有效的斜接方向
+                        n1
+                    } else {
+                        let (mx, my) = (sx / sum_length, sy / sum_length);
+                        // 斜接长度 = 1 / cos(半角)，并按上限截断
+                        let cos_half = mx * n1.0 + my * n1.1;
+                        let miter_length = (1.0 / cos_half).min(MITER_LIMIT);
+                        (mx * miter_length, my * miter_length)
+                    }
+                }
+                (Some(n), None) | (None, Some(n)) => n,
+                (None, None) => (0.0, 0.0),
+            };
+
+            (x + nx * offset, y + ny * offset)
+        })
+        .collect()
+}
+
+/// 计算点集的最小包围圆
+///
+/// 使用 Welzl 算法的迭代形式求精确解，适用于"所有标记是否都在某中心 R 范围内"之类的判断
+///
+/// # 参数
+/// - `points`: 点集（如匹配中心点或路径顶点）
+///
+/// # 返回
+/// ((圆心 x, 圆心 y), 半径)；点集为空时返回 None，只有一个点时半径为 0
+///
+/// # 示例
+/// ```rust
+/// use math_utils::bounding_circle;
+///
+/// let ((cx, cy), radius) = bounding_circle(&[(0.0, 0.0), (10.0, 0.0), (5.0, 1.0)]).unwrap();
+/// assert!((cx - 5.0).abs() < 1e-10 && cy.abs() < 1e-10);
+/// assert!((radius - 5.0).abs() < 1e-10);
+/// ```
+pub fn bounding_circle(points: &[(f64, f64)]) -> Option<((f64, f64), f64)> {
+    let first = *points.first()?;
+    let mut circle = (first, 0.0);
+
+    for i in 1..points.len() {
+        if circle_contains(circle, points[i]) {
+            continue;
+        }
+
+        // points[i] 必在边界上
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if circle_contains(circle, points[j]) {
+                continue;
+            }
+
+            // points[i]、points[j] 必在边界上
+            circle = circle_from_two(points[i], points[j]);
+            for k in 0..j {
+                if !circle_contains(circle, points[k]) {
+                    circle = circle_from_three(points[i], points[j], points[k]);
+                }
+            }
+        }
+    }
+
+    Some(circle)
+}
+
+/// 判断点是否在圆内（含边界，留出浮点误差）
+fn circle_contains(circle: ((f64, f64), f64), point: (f64, f64)) -> bool {
+    let ((cx, cy), radius) = circle;
+    calculate_distance(cx, cy, point.0, point.1) <= radius + 1e-9 * radius.max(1.0)
+}
+
+/// 以两点为直径的圆
+fn circle_from_two(a: (f64, f64), b: (f64, f64)) -> ((f64, f64), f64) {
+    let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    (center, calculate_distance(a.0, a.1, b.0, b.1) / 2.0)
+}
+
+/// 三点的外接圆；三点共线时退化为以最远两点为直径的圆
+fn circle_from_three(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> ((f64, f64), f64) {
+    let (bx, by) = (b.0 - a.0, b.1 - a.1);
+    let (cx, cy) = (c.0 - a.0, c.1 - a.1);
+    let d = 2.0 * (bx * cy - by * cx);
+
+    if d.abs() < 1e-12 {
+        return [circle_from_two(a, b), circle_from_two(a, c), circle_from_two(b, c)]
+            .into_iter()
+            .max_by(|x, y| x.1.partial_cmp(&y.1).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap_or(circle_from_two(a, b));
+    }
+
+    let b_sq = bx * bx + by * by;
+    let c_sq = cx * cx + cy * cy;
+    let ux = (cy * b_sq - by * c_sq) / d;
+    let uy = (bx * c_sq - cx * b_sq) / d;
+
+    ((a.0 + ux, a.1 + uy), (ux * ux + uy * uy).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_distance() {
+        // 测试 3-4-5 直角三角形
+        let distance = calculate_distance(0.0, 0.0, 3.0, 4.0);
+        assert!((distance - 5.0).abs() < 1e-10);
+        
+        // 测试相同点
+        let distance = calculate_distance(5.0, 5.0, 5.0, 5.0);
         assert!((distance - 0.0).abs() < 1e-10);
     }
 
@@ -432,4 +1533,548 @@ mod tests {
         assert_eq!(direction, RotationDirection::Left);
         assert!((angle - 180.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_rotate_path() {
+        // 正上方的点绕原点顺时针旋转 90 度，变为正右方
+        let rotated = rotate_path(&[(0.0, -10.0)], (0.0, 0.0), 90.0);
+        assert!((rotated[0].0 - 10.0).abs() < 1e-10);
+        assert!(rotated[0].1.abs() < 1e-10);
+
+        // 旋转后的朝向角度增加了旋转角度
+        let path = vec![(0.0, 0.0), (3.0, -4.0)];
+        let rotated = rotate_path(&path, (0.0, 0.0), 30.0);
+        let before = calculate_angle_old(path[0].0, path[0].1, path[1].0, path[1].1);
+        let after = calculate_angle_old(rotated[0].0, rotated[0].1, rotated[1].0, rotated[1].1);
+        assert!((after - before - 30.0).abs() < 1e-9);
+
+        // 中心点本身不动
+        let rotated = rotate_path(&[(5.0, 5.0)], (5.0, 5.0), 123.0);
+        assert!((rotated[0].0 - 5.0).abs() < 1e-10);
+        assert!((rotated[0].1 - 5.0).abs() < 1e-10);
+
+        // 空路径
+        assert!(rotate_path(&[], (0.0, 0.0), 90.0).is_empty());
+    }
+
+    #[test]
+    fn test_translate_and_scale_path() {
+        let path = vec![(0.0, 0.0), (10.0, 10.0)];
+        assert_eq!(translate_path(&path, (5.0, -5.0)), vec![(5.0, -5.0), (15.0, 5.0)]);
+
+        let scaled = scale_path(&path, (10.0, 10.0), 0.5);
+        assert_eq!(scaled, vec![(5.0, 5.0), (10.0, 10.0)]);
+    }
+
+    #[test]
+    fn test_calculate_rotation_angle_with_deadzone() {
+        // 目标角度 90 度，当前朝向 89.5 度，在 1 度死区内
+        let (direction, angle) = calculate_rotation_angle_with_deadzone(89.5, 0.0, 0.0, 1.0, 0.0, 1.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert_eq!(angle, 0.0);
+
+        // 目标角度 90 度，当前朝向 90.5 度，左转 0.5 度也在死区内
+        let (direction, angle) = calculate_rotation_angle_with_deadzone(90.5, 0.0, 0.0, 1.0, 0.0, 1.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert_eq!(angle, 0.0);
+
+        // 超出死区时与 calculate_rotation_angle_old 结果一致
+        let (direction, angle) = calculate_rotation_angle_with_deadzone(180.0, 0.0, 0.0, 1.0, 0.0, 1.0);
+        assert_eq!(direction, RotationDirection::Left);
+        assert!((angle - 90.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_calculate_rotation_angle_with_heading() {
+        // 当前朝向 0 度，目的地在正右方，右转 90 度后朝向 90 度
+        let (direction, angle, heading) = calculate_rotation_angle_with_heading(0.0, 0.0, 0.0, 1.0, 0.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 90.0).abs() < 1e-10);
+        assert!((heading - 90.0).abs() < 1e-10);
+
+        // 当前朝向 90 度，目的地在正左方，左转 180 度后朝向 270 度
+        let (direction, angle, heading) = calculate_rotation_angle_with_heading(90.0, 0.0, 0.0, -1.0, 0.0);
+        assert_eq!(direction, RotationDirection::Left);
+        assert!((angle - 180.0).abs() < 1e-10);
+        assert!((heading - 270.0).abs() < 1e-10);
+
+        // 两点相同，朝向保持不变
+        let (direction, angle, heading) = calculate_rotation_angle_with_heading(45.0, 1.0, 1.0, 1.0, 1.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert_eq!(angle, 0.0);
+        assert!((heading - 45.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_angular_distance() {
+        // 跨越 0 度
+        assert!((angular_distance(350.0, 10.0) - 20.0).abs() < 1e-10);
+        assert!((angular_distance(10.0, 350.0) - 20.0).abs() < 1e-10);
+
+        // 正好相反
+        assert!((angular_distance(0.0, 180.0) - 180.0).abs() < 1e-10);
+
+        // 超出 0-360 的输入
+        assert!((angular_distance(725.0, 0.0) - 5.0).abs() < 1e-10);
+        assert!((angular_distance(-90.0, 90.0) - 180.0).abs() < 1e-10);
+
+        // 相同角度
+        assert!(angular_distance(45.0, 45.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_offset_path() {
+        // 直线：向东行进，右侧是 y 增大的方向
+        let offset = offset_path(&[(0.0, 0.0), (10.0, 0.0)], 2.0);
+        assert_eq!(offset, vec![(0.0, 2.0), (10.0, 2.0)]);
+
+        // 负偏移在左侧
+        let offset = offset_path(&[(0.0, 0.0), (10.0, 0.0)], -2.0);
+        assert_eq!(offset, vec![(0.0, -2.0), (10.0, -2.0)]);
+
+        // 直角拐弯（东 -> 南），拐角处斜接到 (9, 1)
+        let offset = offset_path(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)], 1.0);
+        assert!((offset[1].0 - 9.0).abs() < 1e-10);
+        assert!((offset[1].1 - 1.0).abs() < 1e-10);
+        assert!((offset[2].0 - 9.0).abs() < 1e-10);
+        assert!((offset[2].1 - 10.0).abs() < 1e-10);
+
+        // 尖角处斜接长度被截断
+        let offset = offset_path(&[(0.0, 0.0), (10.0, 0.0), (0.0, 0.1)], 1.0);
+        let (dx, dy) = (offset[1].0 - 10.0, offset[1].1);
+        assert!((dx * dx + dy * dy).sqrt() <= MITER_LIMIT + 1e-10);
+
+        // 重复点沿用相邻线段的方向
+        let offset = offset_path(&[(0.0, 0.0), (0.0, 0.0), (10.0, 0.0)], 1.0);
+        assert_eq!(offset, vec![(0.0, 1.0), (0.0, 1.0), (10.0, 1.0)]);
+
+        // 退化情况
+        assert!(offset_path(&[], 1.0).is_empty());
+        assert_eq!(offset_path(&[(1.0, 1.0)], 1.0), vec![(1.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_net_rotation() {
+        // 向北 -> 向东 -> 向南，共右转 180 度
+        let path = vec![(0.0, 10.0), (0.0, 0.0), (10.0, 0.0), (10.0, 10.0)];
+        let (direction, angle) = net_rotation(&path);
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 180.0).abs() < 1e-10);
+
+        // 向北 -> 向西，左转 90 度（跨越 0 度也按最短方向计算）
+        let path = vec![(0.0, 10.0), (0.0, 0.0), (-10.0, 0.0)];
+        let (direction, angle) = net_rotation(&path);
+        assert_eq!(direction, RotationDirection::Left);
+        assert!((angle - 90.0).abs() < 1e-10);
+
+        // S 形：先右转 90 再左转 90，相互抵消
+        let path = vec![(0.0, 10.0), (0.0, 0.0), (10.0, 0.0), (10.0, -10.0)];
+        let (_, angle) = net_rotation(&path);
+        assert!(angle.abs() < 1e-10);
+
+        // 重复点不影响结果
+        let path = vec![(0.0, 10.0), (0.0, 0.0), (0.0, 0.0), (10.0, 0.0)];
+        let (direction, angle) = net_rotation(&path);
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 90.0).abs() < 1e-10);
+
+        // 退化情况
+        assert_eq!(net_rotation(&[]), (RotationDirection::Right, 0.0));
+        assert_eq!(net_rotation(&[(0.0, 0.0), (1.0, 0.0)]), (RotationDirection::Right, 0.0));
+    }
+
+    #[test]
+    fn test_dedup_path() {
+        // 完全重复的点
+        let path = vec![(0.0, 0.0), (0.0, 0.0), (10.0, 0.0), (10.0, 0.0)];
+        assert_eq!(dedup_path(&path, 0.0), vec![(0.0, 0.0), (10.0, 0.0)]);
+
+        // 近似重复的点，与上一个保留点比较
+        let path = vec![(0.0, 0.0), (0.3, 0.0), (0.6, 0.0), (5.0, 0.0)];
+        assert_eq!(dedup_path(&path, 0.5), vec![(0.0, 0.0), (0.6, 0.0), (5.0, 0.0)]);
+
+        // 空路径
+        assert!(dedup_path(&[], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_generate_new_path_array_with_dedup() {
+        let path = vec![(0.0, 0.0), (10.0, 10.0), (10.0, 10.0), (20.0, 20.0)];
+
+        let new_path = generate_new_path_array_with_dedup(&path, (9.0, 9.0), Some(0.5));
+        assert_eq!(new_path, vec![(10.0, 10.0), (20.0, 20.0)]);
+
+        // 不去重时与 generate_new_path_array 一致
+        let new_path = generate_new_path_array_with_dedup(&path, (9.0, 9.0), None);
+        assert_eq!(new_path, generate_new_path_array(&path, (9.0, 9.0)));
+    }
+
+    #[test]
+    fn test_path_cost() {
+        let bent = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)];
+
+        // 无转向代价时等于路径长度
+        assert!((path_cost(&bent, 0.0) - 20.0).abs() < 1e-10);
+
+        // 一个 90 度拐角
+        let expected = 20.0 + 2.0 * std::f64::consts::FRAC_PI_2;
+        assert!((path_cost(&bent, 2.0) - expected).abs() < 1e-10);
+
+        // 左转和右转的代价相同
+        let bent_left = vec![(0.0, 0.0), (10.0, 0.0), (10.0, -10.0)];
+        assert!((path_cost(&bent_left, 2.0) - expected).abs() < 1e-10);
+
+        assert_eq!(path_cost(&[(1.0, 1.0)], 2.0), 0.0);
+    }
+
+    #[test]
+    fn test_turn_toward_path() {
+        // 向东 -> 向南的折线
+        let path = vec![(0.0, 0.0), (100.0, 0.0), (100.0, 100.0)];
+
+        // 靠近第一段，朝北，右转 90 度对准向东
+        let (direction, angle) = turn_toward_path(&path, (30.0, 2.0), 0.0).unwrap();
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 90.0).abs() < 1e-10);
+
+        // 靠近第二段，朝东，右转 90 度对准向南
+        let (direction, angle) = turn_toward_path(&path, (98.0, 70.0), 90.0).unwrap();
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 90.0).abs() < 1e-10);
+
+        // 已经对准时不需要转动
+        let (_, angle) = turn_toward_path(&path, (30.0, 2.0), 90.0).unwrap();
+        assert_eq!(angle, 0.0);
+
+        // 无有效线段
+        assert!(turn_toward_path(&[(1.0, 1.0)], (0.0, 0.0), 0.0).is_none());
+        assert!(turn_toward_path(&[(1.0, 1.0), (1.0, 1.0)], (0.0, 0.0), 0.0).is_none());
+    }
+
+    #[test]
+    fn test_lerp_angle() {
+        // 跨越 0 度时走最短弧
+        assert!((lerp_angle(350.0, 10.0, 0.5) - 0.0).abs() < 1e-10);
+        assert!((lerp_angle(10.0, 350.0, 0.5) - 0.0).abs() < 1e-10);
+        assert!((lerp_angle(350.0, 10.0, 0.25) - 355.0).abs() < 1e-10);
+
+        // 端点
+        assert!((lerp_angle(30.0, 90.0, 0.0) - 30.0).abs() < 1e-10);
+        assert!((lerp_angle(30.0, 90.0, 1.0) - 90.0).abs() < 1e-10);
+
+        // 结果总在 [0, 360)
+        let angle = lerp_angle(-30.0, 400.0, 1.0);
+        assert!((0.0..360.0).contains(&angle));
+        assert!((angle - 40.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_generate_loop_path_array() {
+        let path = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+
+        let new_path = generate_loop_path_array(&path, (11.0, 9.0));
+        assert_eq!(new_path, vec![(10.0, 10.0), (0.0, 10.0), (0.0, 0.0), (10.0, 0.0)]);
+
+        // 最近点是起点时原样返回
+        assert_eq!(generate_loop_path_array(&path, (-1.0, -1.0)), path);
+
+        // 显式闭合的路径不会重复经过起点
+        let mut closed = path.clone();
+        closed.push((0.0, 0.0));
+        assert_eq!(generate_loop_path_array(&closed, (11.0, 9.0)), new_path);
+
+        // 退化情况
+        assert!(generate_loop_path_array(&[], (0.0, 0.0)).is_empty());
+        assert_eq!(generate_loop_path_array(&[(5.0, 5.0)], (0.0, 0.0)), vec![(5.0, 5.0)]);
+        assert_eq!(generate_loop_path_array(&[(5.0, 5.0), (5.0, 5.0)], (0.0, 0.0)), vec![(5.0, 5.0)]);
+    }
+
+    #[test]
+    fn test_bounding_circle() {
+        assert!(bounding_circle(&[]).is_none());
+        assert_eq!(bounding_circle(&[(3.0, 4.0)]), Some(((3.0, 4.0), 0.0)));
+
+        // 两点：以两点为直径
+        let ((cx, cy), radius) = bounding_circle(&[(0.0, 0.0), (6.0, 8.0)]).unwrap();
+        assert!((cx - 3.0).abs() < 1e-10 && (cy - 4.0).abs() < 1e-10);
+        assert!((radius - 5.0).abs() < 1e-10);
+
+        // 直角三角形：外接圆圆心在斜边中点
+        let ((cx, cy), radius) = bounding_circle(&[(0.0, 0.0), (6.0, 0.0), (0.0, 8.0)]).unwrap();
+        assert!((cx - 3.0).abs() < 1e-10 && (cy - 4.0).abs() < 1e-10);
+        assert!((radius - 5.0).abs() < 1e-10);
+
+        // 正方形四个角加中心点
+        let points = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0)];
+        let ((cx, cy), radius) = bounding_circle(&points).unwrap();
+        assert!((cx - 1.0).abs() < 1e-10 && (cy - 1.0).abs() < 1e-10);
+        assert!((radius - 2.0f64.sqrt()).abs() < 1e-10);
+
+        // 共线点
+        let ((cx, _), radius) = bounding_circle(&[(0.0, 0.0), (5.0, 0.0), (10.0, 0.0)]).unwrap();
+        assert!((cx - 5.0).abs() < 1e-10);
+        assert!((radius - 5.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_within_tolerance() {
+        assert!(within_tolerance((1.0, 1.0), (4.0, 5.0), 5.0));
+        assert!(!within_tolerance((1.0, 1.0), (4.0, 5.0), 4.999));
+        assert!(within_tolerance((2.0, 2.0), (2.0, 2.0), 0.0));
+        assert!(!within_tolerance((2.0, 2.0), (2.0, 2.0), -1.0));
+
+        assert!(within_tolerance_i32((0, 0), (-3, -4), 5.0));
+        assert!(!within_tolerance_i32((0, 0), (-3, -4), 4.5));
+    }
+
+    #[test]
+    fn test_calculate_angle() {
+        assert_eq!(calculate_angle(0.0, 0.0, 0.0, -5.0), Some(0.0));
+        assert_eq!(calculate_angle(0.0, 0.0, 5.0, 0.0), Some(90.0));
+        assert_eq!(calculate_angle(0.0, 0.0, 0.0, 5.0), Some(180.0));
+        assert_eq!(calculate_angle(0.0, 0.0, -5.0, 0.0), Some(270.0));
+        assert_eq!(calculate_angle(2.0, 3.0, 2.0, 3.0), None);
+
+        // 各象限与旧版本结果一致
+        for &(x2, y2) in &[(3.0, -4.0), (3.0, 4.0), (-3.0, 4.0), (-3.0, -4.0), (1.0, -0.001)] {
+            let angle = calculate_angle(0.0, 0.0, x2, y2).unwrap();
+            let old = calculate_angle_old(0.0, 0.0, x2, y2);
+            assert!((angle - old).abs() < 1e-9, "({}, {}): {} != {}", x2, y2, angle, old);
+        }
+    }
+
+    #[test]
+    fn test_normalize_angle() {
+        assert!((normalize_angle(0.0) - 0.0).abs() < 1e-10);
+        assert!((normalize_angle(180.0) - 180.0).abs() < 1e-10);
+        assert!((normalize_angle(-180.0) - 180.0).abs() < 1e-10);
+        assert!((normalize_angle(190.0) - -170.0).abs() < 1e-10);
+        assert!((normalize_angle(725.0) - 5.0).abs() < 1e-10);
+        assert!((normalize_angle(-400.0) - -40.0).abs() < 1e-10);
+        assert!((normalize_angle(1080.0) - 0.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_calculate_rotation_angle_old_out_of_range() {
+        // 朝向 725 度即 5 度，目的地在正右方（90 度），右转 85 度
+        let (direction, angle) = calculate_rotation_angle_old(725.0, 0.0, 0.0, 1.0, 0.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 85.0).abs() < 1e-10);
+
+        // 朝向 -400 度即 320 度，目的地在正右方，右转 130 度
+        let (direction, angle) = calculate_rotation_angle_old(-400.0, 0.0, 0.0, 1.0, 0.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 130.0).abs() < 1e-10);
+
+        // 朝向 1000 度即 280 度，目的地在正上方（0 度），右转 80 度
+        let (direction, angle) = calculate_rotation_angle_old(1000.0, 0.0, 0.0, 0.0, -1.0);
+        assert_eq!(direction, RotationDirection::Right);
+        assert!((angle - 80.0).abs() < 1e-10);
+
+        // 朝向 -1170 度即 270 度，目的地在正下方（180 度），左转 90 度
+        let (direction, angle) = calculate_rotation_angle_old(-1170.0, 0.0, 0.0, 0.0, 1.0);
+        assert_eq!(direction, RotationDirection::Left);
+        assert!((angle - 90.0).abs() < 1e-10);
+
+        // 朝向 810 度即 90 度，目的地在正左方（270 度），相差 180 度，左转 180 度
+        let (direction, angle) = calculate_rotation_angle_old(810.0, 0.0, 0.0, -1.0, 0.0);
+        assert_eq!(direction, RotationDirection::Left);
+        assert!((angle - 180.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_path_length() {
+        assert_eq!(path_length(&[]), 0.0);
+        assert_eq!(path_length(&[(1.0, 1.0)]), 0.0);
+        assert_eq!(path_length(&[(0.0, 0.0), (3.0, 4.0), (3.0, 10.0)]), 11.0);
+    }
+
+    #[test]
+    fn test_cumulative_distances() {
+        assert!(cumulative_distances(&[]).is_empty());
+        assert_eq!(cumulative_distances(&[(1.0, 1.0)]), vec![0.0]);
+
+        let path = vec![(0.0, 0.0), (3.0, 4.0), (3.0, 4.0), (3.0, 10.0)];
+        let distances = cumulative_distances(&path);
+        assert_eq!(distances, vec![0.0, 5.0, 5.0, 11.0]);
+        assert_eq!(*distances.last().unwrap(), path_length(&path));
+    }
+
+    #[test]
+    fn test_resample_path() {
+        // 总长度不是间距的整数倍，最后一段较短
+        let path = vec![(0.0, 0.0), (10.0, 0.0)];
+        assert_eq!(
+            resample_path(&path, 3.0),
+            vec![(0.0, 0.0), (3.0, 0.0), (6.0, 0.0), (9.0, 0.0), (10.0, 0.0)]
+        );
+
+        // 正好是整数倍时终点不重复
+        assert_eq!(resample_path(&path, 5.0), vec![(0.0, 0.0), (5.0, 0.0), (10.0, 0.0)]);
+
+        // 跨越拐角插值
+        let path = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)];
+        assert_eq!(
+            resample_path(&path, 1.5),
+            vec![(0.0, 0.0), (1.5, 0.0), (2.0, 1.0), (2.0, 2.0)]
+        );
+
+        // 间距大于路径总长度时只返回首尾
+        assert_eq!(resample_path(&path, 100.0), vec![(0.0, 0.0), (2.0, 2.0)]);
+
+        // 采样后相邻点的间距均匀
+        let path = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 0.5), (7.0, 0.5), (7.0, 9.0)];
+        let resampled = resample_path(&path, 0.7);
+        for pair in resampled[..resampled.len() - 1].windows(2) {
+            let distance = calculate_distance(pair[0].0, pair[0].1, pair[1].0, pair[1].1);
+            assert!(distance <= 0.7 + 1e-9);
+        }
+        assert_eq!(resampled.last(), path.last());
+
+        // 退化输入
+        assert!(resample_path(&[], 1.0).is_empty());
+        assert_eq!(resample_path(&[(1.0, 1.0)], 1.0), vec![(1.0, 1.0)]);
+        assert_eq!(resample_path(&path, 0.0), path);
+    }
+
+    #[test]
+    fn test_path_tracker() {
+        let path: Vec<(f64, f64)> = (0..100).map(|i| (i as f64 * 10.0, 0.0)).collect();
+        let mut tracker = PathTracker::with_window(path.clone(), 4);
+        assert_eq!(tracker.current_index(), None);
+
+        // 第一次查询遍历整条路径，结果与 generate_new_path_array 一致
+        let current = (502.0, 3.0);
+        assert_eq!(tracker.advance(current), generate_new_path_array(&path, current).as_slice());
+        assert_eq!(tracker.current_index(), Some(50));
+
+        // 沿路线前进
+        for step in 0..30 {
+            let current = (502.0 + step as f64 * 13.0, -2.0);
+            assert_eq!(tracker.advance(current), generate_new_path_array(&path, current).as_slice());
+        }
+
+        // 不会向后回退
+        let index = tracker.current_index().unwrap();
+        assert_eq!(tracker.advance((0.0, 0.0))[0], path[index]);
+
+        // 重置后重新遍历整条路径
+        tracker.reset();
+        assert_eq!(tracker.advance((0.0, 0.0))[0], (0.0, 0.0));
+
+        // 空路径
+        let mut tracker = PathTracker::new(Vec::new());
+        assert!(tracker.advance((1.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn test_calculate_distance_squared() {
+        assert_eq!(calculate_distance_squared(0.0, 0.0, 3.0, 4.0), 25.0);
+        assert_eq!(calculate_distance_squared(1.0, 1.0, 1.0, 1.0), 0.0);
+        assert_eq!(calculate_distance_squared_i32(-1, -1, 2, 3), 25.0);
+
+        let (x1, y1, x2, y2) = (1.5, -2.0, 7.25, 3.5);
+        let distance = calculate_distance(x1, y1, x2, y2);
+        assert!((calculate_distance_squared(x1, y1, x2, y2) - distance * distance).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_distance_point_to_segment() {
+        // 投影在线段内
+        assert_eq!(distance_point_to_segment(5.0, 3.0, 0.0, 0.0, 10.0, 0.0), 3.0);
+        assert_eq!(distance_point_to_segment(5.0, -3.0, 0.0, 0.0, 10.0, 0.0), 3.0);
+
+        // 投影在线段两端之外，取到端点的距离
+        assert_eq!(distance_point_to_segment(-3.0, 4.0, 0.0, 0.0, 10.0, 0.0), 5.0);
+        assert_eq!(distance_point_to_segment(13.0, 4.0, 0.0, 0.0, 10.0, 0.0), 5.0);
+
+        // 点在线段上
+        assert_eq!(distance_point_to_segment(2.0, 2.0, 0.0, 0.0, 4.0, 4.0), 0.0);
+
+        // 斜线段
+        let distance = distance_point_to_segment(0.0, 2.0, 0.0, 0.0, 2.0, 2.0);
+        assert!((distance - 2.0_f64.sqrt()).abs() < 1e-10);
+
+        // 长度为 0 的线段
+        assert_eq!(distance_point_to_segment(4.0, 5.0, 1.0, 1.0, 1.0, 1.0), 5.0);
+    }
+
+    #[test]
+    fn test_rotate_point() {
+        // 顺时针旋转：上 -> 右 -> 下 -> 左
+        let (x, y) = rotate_point(0.0, -10.0, 0.0, 0.0, 90.0);
+        assert!((x - 10.0).abs() < 1e-10 && y.abs() < 1e-10);
+        let (x, y) = rotate_point(0.0, -10.0, 0.0, 0.0, 180.0);
+        assert!(x.abs() < 1e-10 && (y - 10.0).abs() < 1e-10);
+        let (x, y) = rotate_point(0.0, -10.0, 0.0, 0.0, -90.0);
+        assert!((x + 10.0).abs() < 1e-10 && y.abs() < 1e-10);
+
+        // 旋转后的方位角增加旋转角度，与右转方向一致
+        let (x, y) = rotate_point(13.0, 6.0, 10.0, 10.0, 40.0);
+        let before = calculate_angle(10.0, 10.0, 13.0, 6.0).unwrap();
+        let after = calculate_angle(10.0, 10.0, x, y).unwrap();
+        assert!((normalize_angle(after - before) - 40.0).abs() < 1e-9);
+
+        // 与 rotate_path 结果一致
+        let path = vec![(1.0, 2.0), (-3.0, 4.0)];
+        let rotated = rotate_path(&path, (0.5, 0.5), 75.0);
+        for (&(px, py), &(rx, ry)) in path.iter().zip(&rotated) {
+            let (x, y) = rotate_point(px, py, 0.5, 0.5, 75.0);
+            assert!((x - rx).abs() < 1e-12 && (y - ry).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_nearest_point_index() {
+        let path = vec![(0.0, 0.0), (10.0, 10.0), (20.0, 20.0), (30.0, 30.0)];
+        assert_eq!(nearest_point_index(&path, (18.0, 19.0)), Some(2));
+        assert_eq!(nearest_point_index(&path, (-100.0, 0.0)), Some(0));
+        assert_eq!(nearest_point_index(&path, (100.0, 100.0)), Some(3));
+        assert_eq!(nearest_point_index(&[], (0.0, 0.0)), None);
+
+        // 距离相同时取靠前的点
+        assert_eq!(nearest_point_index(&path, (5.0, 5.0)), Some(0));
+
+        // 与 generate_new_path_array 的结果对应
+        let current = (21.0, 26.0);
+        let index = nearest_point_index(&path, current).unwrap();
+        assert_eq!(generate_new_path_array(&path, current), path[index..].to_vec());
+    }
+
+    #[test]
+    fn test_shortest_angle_diff() {
+        assert!((shortest_angle_diff(0.0, 90.0) - 90.0).abs() < 1e-10);
+        assert!((shortest_angle_diff(90.0, 0.0) - -90.0).abs() < 1e-10);
+        assert!((shortest_angle_diff(350.0, 10.0) - 20.0).abs() < 1e-10);
+        assert!((shortest_angle_diff(10.0, 350.0) - -20.0).abs() < 1e-10);
+        assert!((shortest_angle_diff(0.0, 180.0) - 180.0).abs() < 1e-10);
+        assert!((shortest_angle_diff(180.0, 0.0) - 180.0).abs() < 1e-10);
+        assert!((shortest_angle_diff(725.0, -355.0) - 0.0).abs() < 1e-10);
+
+        // 符号与 calculate_rotation_angle_old 的方向一致
+        let diff = shortest_angle_diff(200.0, 90.0);
+        let (direction, angle) = calculate_rotation_angle_old(200.0, 0.0, 0.0, 1.0, 0.0);
+        assert_eq!(direction, RotationDirection::Left);
+        assert!((angle - diff.abs()).abs() < 1e-10 && diff < 0.0);
+    }
+
+    #[test]
+    fn test_rotation_direction_to_string() {
+        assert_eq!(RotationDirection::Left.to_string_en(), "Left");
+        assert_eq!(RotationDirection::Right.to_string_en(), "Right");
+        assert_eq!(RotationDirection::Left.to_string_cn(), "左");
+        assert_eq!(RotationDirection::Right.to_string_cn(), "右");
+    }
+
+    #[test]
+    fn test_path_bounds() {
+        assert_eq!(path_bounds(&[]), None);
+        assert_eq!(path_bounds(&[(3.0, -1.0)]), Some((3.0, -1.0, 3.0, -1.0)));
+
+        let path = vec![(10.0, 5.0), (-2.0, 8.0), (4.0, -3.0)];
+        assert_eq!(path_bounds(&path), Some((-2.0, -3.0, 10.0, 8.0)));
+
+        assert_eq!(path_bounds_i32(&[]), None);
+        assert_eq!(path_bounds_i32(&[(5, 1), (0, 7), (3, -4)]), Some((0, -4, 5, 7)));
+    }
 }
@@ -1,4 +1,6 @@
 mod screenshot;
+mod saving;
+mod types;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
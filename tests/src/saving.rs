@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use image_utils::saving::{save_array3_fast, save_array3_via_opencv};
+    use ndarray::Array3;
+    use opencv::core::{MatTraitConst, Vec3b};
+    use opencv::imgcodecs;
+
+    /// 保存纯红色的 RGB 图像后读回，OpenCV 读出的 BGR 像素应为 (0, 0, 255)
+    #[test]
+    fn save_array3_via_opencv_keeps_rgb_order() {
+        let path = std::env::temp_dir().join("auto_utils_save_rgb_round_trip.png");
+        let path = path.to_str().unwrap();
+
+        let mut array = Array3::<u8>::zeros((4, 6, 3));
+        array.slice_mut(ndarray::s![.., .., 0]).fill(255);
+        save_array3_via_opencv(&array, path).unwrap();
+
+        let mat = imgcodecs::imread(path, imgcodecs::IMREAD_COLOR).unwrap();
+        assert_eq!((mat.rows(), mat.cols()), (4, 6));
+        assert_eq!(*mat.at_2d::<Vec3b>(2, 3).unwrap(), Vec3b::from([0, 0, 255]));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    /// RGBA 输入同样按 RGB 顺序保存（Alpha 通道被丢弃）
+    #[test]
+    fn save_array3_via_opencv_keeps_rgba_order() {
+        let path = std::env::temp_dir().join("auto_utils_save_rgba_round_trip.png");
+        let path = path.to_str().unwrap();
+
+        let mut array = Array3::<u8>::zeros((4, 6, 4));
+        array.slice_mut(ndarray::s![.., .., 0]).fill(255);
+        array.slice_mut(ndarray::s![.., .., 3]).fill(255);
+        save_array3_via_opencv(&array, path).unwrap();
+
+        let mat = imgcodecs::imread(path, imgcodecs::IMREAD_COLOR).unwrap();
+        assert_eq!(*mat.at_2d::<Vec3b>(0, 0).unwrap(), Vec3b::from([0, 0, 255]));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    /// 保存转置后的切片（非标准布局），像素位置应与转置后的坐标一致
+    #[test]
+    fn save_array3_fast_accepts_transposed_view() {
+        let path = std::env::temp_dir().join("auto_utils_save_transposed.png");
+        let path = path.to_str().unwrap();
+
+        // R 通道随 x 变化，G 通道随 y 变化
+        let array = Array3::<u8>::from_shape_fn((4, 6, 3), |(y, x, c)| match c {
+            0 => x as u8 * 40,
+            1 => y as u8 * 40,
+            _ => 0,
+        });
+        let transposed = array.view().permuted_axes([1, 0, 2]);
+        assert!(!transposed.is_standard_layout());
+        save_array3_fast(&transposed, path).unwrap();
+
+        // 转置后第 row 行第 col 列对应原图的 (x = row, y = col)
+        let mat = imgcodecs::imread(path, imgcodecs::IMREAD_COLOR).unwrap();
+        assert_eq!((mat.rows(), mat.cols()), (6, 4));
+        assert_eq!(*mat.at_2d::<Vec3b>(5, 3).unwrap(), Vec3b::from([0, 120, 200]));
+
+        // 切片同样可以保存
+        let sliced = array.slice(ndarray::s![1..3, ..;2, ..]);
+        save_array3_fast(&sliced, path).unwrap();
+        let mat = imgcodecs::imread(path, imgcodecs::IMREAD_COLOR).unwrap();
+        assert_eq!((mat.rows(), mat.cols()), (2, 3));
+        assert_eq!(*mat.at_2d::<Vec3b>(1, 2).unwrap(), Vec3b::from([0, 80, 160]));
+
+        std::fs::remove_file(path).ok();
+    }
+}
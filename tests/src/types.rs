@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use image_utils::types::{ColorMetric, Point, RgbColor};
+
+    #[test]
+    fn rgb_color_from_hex_accepts_all_formats() {
+        let expected = RgbColor::new(0x2B, 0x2D, 0x30);
+        assert_eq!(RgbColor::from_hex("#2B2D30").unwrap(), expected);
+        assert_eq!(RgbColor::from_hex("2b2d30").unwrap(), expected);
+        assert_eq!(RgbColor::from_hex("#F80").unwrap(), RgbColor::new(0xFF, 0x88, 0x00));
+    }
+
+    #[test]
+    fn rgb_color_from_hex_rejects_malformed_input() {
+        for input in ["", "#", "#12345", "#1234567", "#GGHHII", "#+1+2+3", "#2B2D3€"] {
+            assert!(RgbColor::from_hex(input).is_err(), "{:?} 应解析失败", input);
+        }
+    }
+
+    #[test]
+    fn rgb_color_hex_round_trip() {
+        let color = RgbColor::new(0x01, 0xAB, 0xFF);
+        assert_eq!(color.to_hex(), "#01ABFF");
+        assert_eq!(RgbColor::from_hex(&color.to_hex()).unwrap(), color);
+    }
+
+    #[test]
+    fn rgb_color_distances() {
+        let a = RgbColor::new(10, 20, 30);
+        let b = RgbColor::new(13, 24, 30);
+        assert_eq!(a.distance(&b), 7);
+        assert_eq!(b.distance(&a), 7);
+        assert!((a.distance_euclidean(&b) - 5.0).abs() < 1e-9);
+        assert_eq!(RgbColor::new(0, 0, 0).distance(&RgbColor::new(255, 255, 255)), 765);
+    }
+
+    #[test]
+    fn color_metric_within_tolerance() {
+        let a = RgbColor::new(100, 100, 100);
+        let b = RgbColor::new(103, 104, 100);
+        assert!(!ColorMetric::Manhattan.within(&a, &b, 5));
+        assert!(ColorMetric::Euclidean.within(&a, &b, 5));
+        assert!(!ColorMetric::Euclidean.within(&a, &b, 4));
+        assert_eq!(ColorMetric::default(), ColorMetric::Manhattan);
+    }
+
+    #[test]
+    fn point_arithmetic_and_tuple_conversions() {
+        let a = Point::new(3, 4);
+        let b: Point<i32> = (1, 1).into();
+
+        let sum: (i32, i32) = (a + b).into();
+        let diff: (i32, i32) = (a - b).into();
+        assert_eq!(sum, (4, 5));
+        assert_eq!(diff, (2, 3));
+
+        assert!((a.distance(&Point::new(0, 0)) - 5.0).abs() < 1e-9);
+        assert!((Point::new(0.0, 0.0).distance(&Point::new(3.0, 4.0)) - 5.0).abs() < 1e-9);
+    }
+}